@@ -1,19 +1,19 @@
 use tracing_bunyan_formatter::{BunyanFormattingLayer, JsonStorageLayer};
-use tracing_subscriber::{Registry, EnvFilter};
+use tracing_subscriber::Registry;
 use tracing_appender::non_blocking;
 use tracing_subscriber::layer::SubscriberExt;
 use game_engine::game_loop::{GameLoop, GameLoopError};
 use game_engine::input::multi_input::MultiInput;
 use std::fmt::{Debug, Formatter};
 use game_engine::scenes::{SceneLoader, SCENES_DIR, Scene};
-use game_engine::load::{JSONLoad, LOAD_PATH, JSON_FILE, load_deserializable_from_file, create_entity_vec, load_deserializable_from_json};
+use game_engine::load::{JSONLoad, load_deserializable_from_file, create_entity_vec, load_deserializable_from_json};
 use anyhow::{Result, Error};
 use game_engine::game::GameWrapper;
 use specs::{World, WorldExt, WriteStorage, Join, ReadStorage};
 use game_engine::graphics::texture::{TextureHandle, TextureLoader, TEXTURE_LOAD_ID};
 use game_engine::graphics::transform::{Transform, TransformLoader, TRANSFORM_LOAD_ID};
 use game_engine::loading::{DrawTask, Task, GenTask};
-use game_engine::scenes::scene_stack::{SceneStack, SceneStackLoader, SceneTransition, SCENE_STACK_FILE_ID};
+use game_engine::scenes::scene_stack::{SceneStack, SceneStackLoader, SceneTransition};
 use game_engine::globals::texture_dict::{TextureDictLoader, TEXTURE_DICT_LOAD_ID};
 use game_engine::camera::orthographic_camera::{OrthographicCameraLoader, ORTHOGRAPHIC_CAMERA_LOAD_ID};
 use game_engine::camera::Camera;
@@ -23,35 +23,59 @@ use luminance_front::context::GraphicsContext;
 use luminance_front::texture::Dim2;
 use luminance_front::pipeline::PipelineState;
 use game_engine::graphics::render::Renderer;
-use glam::{Mat4, Vec3};
+use glam::Vec3;
 use game_engine::components::{ComponentMux, ComponentLoader};
 use std::sync::{Arc, RwLock};
+use std::cell::RefCell;
 use serde::Deserialize;
 use glfw::Key;
 use serde_json::from_value;
 use game_engine::graphics::Context;
 use std::ops::{Deref, DerefMut};
-use std::sync::atomic::AtomicBool;
-use std::sync::atomic::Ordering::{Release, Acquire, Relaxed};
+use std::sync::atomic::{AtomicBool, AtomicU64};
+use std::sync::atomic::Ordering::{Release, Acquire, AcqRel, Relaxed};
+use test_harness::cli::args;
+use test_harness::paths::{artifacts_dir, log_dir};
+use test_harness::artifacts::describe_input;
+use test_harness::recording::InputRecording;
+use test_harness::context::{TestContext, report};
+use test_harness::soak::{SoakMonitor, SOAK_CHECKS, SOAK_FRAME_WINDOW};
+use test_harness::cycle::SceneCycler;
+use test_harness::leak::{LeakCheck, ResourceCounts};
+use test_harness::stats::FrameStats;
+use test_harness::projection::screen_projection;
+use test_harness::baseline::{frame_time_gate, BASELINES_DIR, FRAME_TIME_GATE_CHECKS};
+use std::path::PathBuf;
 
 const CAMERA_TEST_ID: &str = "camera_test";
 const CAMERA_TEST_SCENE_ID: &str = "camera_test_scene";
+/// Checks `update` records once `--frames` is reached.
+const TRANSFORM_CHECKS: usize = 1;
 
 fn main() -> Result<(), GameLoopError> {
+    args().enter_working_dir().expect("Failed to find the workspace assets");
+
     let app_name = concat!(env!("CARGO_PKG_NAME"), "-", env!("CARGO_PKG_VERSION")).to_string();
-    let file_appender = tracing_appender::rolling::never("C:/Users/tlmor/game_engine_tests/camera_test/", "camera_test.log");
+    let file_appender = tracing_appender::rolling::never(log_dir(env!("CARGO_PKG_NAME")), "camera_test.log");
     let (non_blocking_writer, _guard) = non_blocking(file_appender);
 
     let bunyan_formatting_layer = BunyanFormattingLayer::new(app_name, non_blocking_writer);
     let subscriber = Registry::default()
-        .with(EnvFilter::from_default_env())
+        .with(args().env_filter())
         .with(JsonStorageLayer)
         .with(bunyan_formatting_layer);
 
     tracing::subscriber::set_global_default(subscriber).expect("Failed to set global default subscriber");
 
     let game_loop: GameLoop<TestGameWrapper, MultiInput> = GameLoop::new();
-    game_loop.run(CAMERA_TEST_ID.to_string())
+    game_loop.run(CAMERA_TEST_ID.to_string())?;
+
+    if !report() {
+        drop(_guard);
+        std::process::exit(1);
+    }
+
+    Ok(())
 }
 
 struct TestGameWrapper;
@@ -73,32 +97,16 @@ impl GameWrapper<MultiInput> for TestGameWrapper {
 
     fn load() -> GenTask<SceneStack<MultiInput>> {
         let ss_loader = SceneStackLoader::new(
-            [
-                LOAD_PATH,
-                CAMERA_TEST_ID,"/",
-                SCENES_DIR,
-                SCENE_STACK_FILE_ID,
-                JSON_FILE
-            ].join(""),
+            args().asset_path(CAMERA_TEST_ID, &[SCENES_DIR], &args().scene),
             TestGameWrapper::scene_factory
         );
 
         let td_loader = TextureDictLoader::new(
-            [
-                LOAD_PATH,
-                CAMERA_TEST_ID,"/",
-                TEXTURE_DICT_LOAD_ID,
-                JSON_FILE
-            ].join("")
+            args().asset_path(CAMERA_TEST_ID, &[], TEXTURE_DICT_LOAD_ID)
         );
 
         let camera_loader = OrthographicCameraLoader::new(
-            [
-                LOAD_PATH,
-                CAMERA_TEST_ID,"/",
-                ORTHOGRAPHIC_CAMERA_LOAD_ID,
-                JSON_FILE
-            ].join("")
+            args().asset_path(CAMERA_TEST_ID, &[], ORTHOGRAPHIC_CAMERA_LOAD_ID)
         );
 
         let td_task = td_loader.load()
@@ -126,10 +134,22 @@ impl GameWrapper<MultiInput> for TestGameWrapper {
 }
 
 pub struct CameraTestScene {
-    sprite_renderer: RwLock<SpriteRenderer>,
-    should_finish: AtomicBool
+    sprite_renderer: RefCell<SpriteRenderer>,
+    should_finish: AtomicBool,
+    frame_count: AtomicU64,
+    frame_stats: FrameStats,
+    start_translations: RefCell<Vec<f32>>,
+    soak: Option<Arc<SoakMonitor>>,
+    recording: Option<InputRecording>,
+    cycler: Option<SceneCycler<CameraTestSceneLoader>>,
+    leak_check: LeakCheck,
+    soak_checked: AtomicBool,
+    gate_checked: AtomicBool,
+    test_context: Option<Arc<TestContext>>
 }
 
+// SpriteRenderer holds GL objects, so the scene is only ever used from the thread that owns the
+// GL context; the RefCells rely on that to skip locking.
 unsafe impl Send for CameraTestScene {}
 
 unsafe impl Sync for CameraTestScene {}
@@ -137,32 +157,73 @@ unsafe impl Sync for CameraTestScene {}
 impl Debug for CameraTestScene {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("Camera Test Scene")
-            .field("Sprite Renderer", &self.sprite_renderer.read()
-                .expect("Failed to acquire read lock for renderer")
-                .render_state
-            )
+            .field("Sprite Renderer", &self.sprite_renderer.borrow().render_state)
             .finish()
     }
 }
 
 impl Scene<MultiInput> for CameraTestScene {
     fn update(&self, ecs: Arc<RwLock<World>>) -> Result<SceneTransition<MultiInput>> {
-        let ecs = ecs.read().expect("Failed to acquire read lock for World.");
+        let ecs = self.frame_stats.timed(|| ecs.read()).expect("Failed to acquire read lock for World.");
         let transforms: ReadStorage<Transform> = ecs.system_data();
 
+        if let Some(test_context) = &self.test_context {
+            let frame = self.frame_count.load(Relaxed);
+            let translations: Vec<f32> = (&transforms).join()
+                .map(|transform| transform.translation[0].load(Relaxed))
+                .collect();
+
+            let mut start_translations = self.start_translations.borrow_mut();
+
+            if frame == 0 {
+                *start_translations = translations.clone();
+            }
+
+            if Some(frame) == args().frames {
+                let expected: Vec<f32> = start_translations.iter()
+                    .map(|start| start + frame as f32)
+                    .collect();
+
+                test_context.expect_eq(translations, expected, "Transforms advance one unit per frame");
+            }
+        }
+
         for transform in (&transforms).join() {
             transform.translation[0].store(transform.translation[0].load(Relaxed) + 1.0, Relaxed);
         }
 
-        Ok(SceneTransition::NONE)
+        self.frame_count.fetch_add(1, Relaxed);
+
+        match &self.cycler {
+            Some(cycler) => Ok(cycler.update(|cycle| {
+                if let (true, Some(test_context)) = (args().leak_cycles.is_some(), &self.test_context) {
+                    self.leak_check.record(cycle, ResourceCounts::of(ecs.deref()), test_context);
+                }
+            })),
+            None => Ok(SceneTransition::NONE)
+        }
     }
 
     fn draw(&self, ecs: Arc<RwLock<World>>) -> Result<()> {
-        let ecs = ecs.read().expect("Failed to acquire read lock for World");
-        let context = ecs.fetch_mut::<Context>();
+        self.frame_stats.tick();
+
+        let ecs = self.frame_stats.timed(|| ecs.read()).expect("Failed to acquire read lock for World");
+
+        if let Some(soak) = &self.soak {
+            soak.tick(ecs.deref());
+        }
+
+        if args().headless {
+            if let Some(test_context) = &self.test_context {
+                test_context.capture_if_failed(ecs.deref(), false)?;
+            }
+
+            return Ok(())
+        }
 
-        let mut context = context.0
-            .write()
+        let context = ecs.fetch::<Context>();
+
+        let mut context = self.frame_stats.timed(|| context.0.write())
             .expect("Failed to acquire write lock for Context");
 
         let back_buffer = context.back_buffer()
@@ -173,19 +234,11 @@ impl Scene<MultiInput> for CameraTestScene {
                 &back_buffer,
                 &PipelineState::default().set_clear_color([0.0, 0.0, 0.0, 1.0]),
                 |pipeline, mut shading_gate| {
-                    self.sprite_renderer.write()
-                        .expect("Failed to acquire write lock for renderer")
+                    self.sprite_renderer.borrow_mut()
                         .render(
                             &pipeline,
                             &mut shading_gate,
-                            &Mat4::orthographic_rh_gl(
-                                0.0,
-                                960.0,
-                                0.0,
-                                540.0,
-                                -1.0,
-                                10.0
-                            ),
+                            &screen_projection(960.0, 540.0),
                             ecs.deref()
                         ).unwrap();
 
@@ -193,14 +246,26 @@ impl Scene<MultiInput> for CameraTestScene {
                 }
             );
 
+        if let Some(test_context) = &self.test_context {
+            test_context.capture_if_failed(ecs.deref(), true)?;
+        }
+
         Ok(())
     }
 
     fn interact(&self, ecs: Arc<RwLock<World>>, input: &MultiInput) -> Result<()> {
-        let ecs = ecs.read().expect("Failed to acquire read lock");
+        let ecs = self.frame_stats.timed(|| ecs.read()).expect("Failed to acquire read lock");
 
         let mut camera = ecs.fetch_mut::<Option<Box<dyn Camera>>>();
 
+        let frame = describe_input(self.frame_count.load(Relaxed), input);
+        if let Some(recording) = &self.recording {
+            recording.record(&frame)?;
+        }
+        if let Some(test_context) = &self.test_context {
+            test_context.record_input(frame);
+        }
+
         if let Some(camera) = camera.deref_mut() {
             for key in input.get_pressed_keys() {
                 match key.key {
@@ -275,7 +340,28 @@ impl Scene<MultiInput> for CameraTestScene {
     }
 
     fn is_finished(&self, _ecs: Arc<RwLock<World>>) -> Result<bool> {
-        return Ok(self.should_finish.load(Acquire))
+        if let (Some(soak), Some(test_context)) = (&self.soak, &self.test_context) {
+            if soak.is_done() && !self.soak_checked.swap(true, AcqRel) {
+                soak.check(test_context);
+            }
+        }
+
+        if let (true, Some(frames), Some(test_context)) = (args().perf_gate, args().frames, &self.test_context) {
+            if self.frame_stats.frames() as u64 >= frames && !self.gate_checked.swap(true, AcqRel) {
+                let baseline_path = PathBuf::from(args().asset_path(CAMERA_TEST_ID, &[BASELINES_DIR], &args().scene));
+                frame_time_gate(&baseline_path, &self.frame_stats.summary(), test_context, args().update_baseline)?;
+            }
+        }
+
+        let checks_done = self.test_context.as_ref()
+            .map_or(false, |test_context| test_context.is_complete());
+
+        let finished = self.should_finish.load(Acquire) || checks_done;
+        if finished {
+            println!("[{}] {}", CAMERA_TEST_SCENE_ID, self.frame_stats.summary());
+        }
+
+        return Ok(finished)
     }
 }
 
@@ -310,6 +396,7 @@ impl ComponentMux for CameraTestSceneLoader {
 impl SceneLoader<MultiInput> for CameraTestSceneLoader {
     fn load_scene(&self) -> GenTask<Box<dyn Scene<MultiInput>>> {
         let entity_paths = self.json.entity_paths.clone();
+        let scene_entity_paths = self.json.entity_paths.clone();
         SpriteRendererLoader::load_default()
             .serialize(
                 Task::new(move |(renderer, ecs): (SpriteRenderer, Arc<RwLock<World>>)| {
@@ -317,10 +404,50 @@ impl SceneLoader<MultiInput> for CameraTestSceneLoader {
                     return Ok(renderer)
                 })
             )
-            .map(|renderer, _ecs| {
+            .map(move |renderer, _ecs| {
+                let mut checks = 0;
+                if args().frames.is_some() {
+                    checks += TRANSFORM_CHECKS;
+                }
+                if args().perf_gate {
+                    checks += FRAME_TIME_GATE_CHECKS;
+                }
+                if args().soak.is_some() {
+                    checks += SOAK_CHECKS;
+                }
+                if let Some(cycles) = args().leak_cycles {
+                    checks += cycles as usize;
+                }
+
+                let soak = args().soak.map(|hours| Arc::new(SoakMonitor::new(hours)));
+
                 Ok(Box::new(CameraTestScene {
-                    sprite_renderer: RwLock::new(renderer),
-                    should_finish: AtomicBool::new(false)
+                    sprite_renderer: RefCell::new(renderer),
+                    should_finish: AtomicBool::new(false),
+                    frame_count: AtomicU64::new(0),
+                    frame_stats: match args().soak {
+                        Some(_) => FrameStats::bounded(SOAK_FRAME_WINDOW),
+                        None => FrameStats::new()
+                    },
+                    start_translations: RefCell::new(Vec::new()),
+                    soak: soak.clone(),
+                    recording: args().record.as_deref().map(InputRecording::create).transpose()?,
+                    cycler: match (args().leak_cycles, soak) {
+                        (Some(cycles), _) => Some(SceneCycler::new(scene_entity_paths.clone(), Some(cycles))),
+                        (None, Some(soak)) => Some(SceneCycler::soak(scene_entity_paths.clone(), soak)),
+                        (None, None) => None
+                    },
+                    leak_check: LeakCheck::new(),
+                    soak_checked: AtomicBool::new(false),
+                    gate_checked: AtomicBool::new(false),
+                    test_context: match checks {
+                        0 => None,
+                        checks => Some(TestContext::register(
+                            CAMERA_TEST_SCENE_ID,
+                            artifacts_dir(env!("CARGO_PKG_NAME"), &args().scene),
+                            checks
+                        )?)
+                    }
                 }) as Box<dyn Scene<MultiInput>>)
             })
     }
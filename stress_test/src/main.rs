@@ -0,0 +1,410 @@
+use tracing_bunyan_formatter::{BunyanFormattingLayer, JsonStorageLayer};
+use tracing_subscriber::Registry;
+use tracing_appender::non_blocking;
+use tracing_subscriber::layer::SubscriberExt;
+use game_engine::game_loop::{GameLoop, GameLoopError};
+use game_engine::input::multi_input::MultiInput;
+use std::fmt::{Debug, Formatter};
+use game_engine::scenes::{SceneLoader, SCENES_DIR, Scene};
+use game_engine::load::{JSONLoad, create_entity_vec};
+use anyhow::{Result, Error};
+use game_engine::game::GameWrapper;
+use specs::{World, WorldExt, Entities, ReadStorage, Join};
+use game_engine::graphics::texture::{TextureHandle, TextureLoader, TEXTURE_LOAD_ID};
+use game_engine::graphics::transform::{Transform, TransformLoader, TRANSFORM_LOAD_ID};
+use game_engine::loading::{Task, GenTask};
+use game_engine::scenes::scene_stack::{SceneStack, SceneStackLoader, SceneTransition};
+use game_engine::globals::texture_dict::{TextureDictLoader, TEXTURE_DICT_LOAD_ID};
+use game_engine::camera::orthographic_camera::{OrthographicCameraLoader, ORTHOGRAPHIC_CAMERA_LOAD_ID};
+use game_engine::camera::Camera;
+use game_engine::graphics::render::sprite_renderer::{SpriteRenderer, SpriteRenderError, SpriteRendererLoader};
+use luminance_front::context::GraphicsContext;
+use luminance_front::texture::Dim2;
+use luminance_front::pipeline::PipelineState;
+use game_engine::graphics::render::Renderer;
+use game_engine::components::{ComponentMux, ComponentLoader};
+use std::sync::{Arc, RwLock};
+use std::cell::RefCell;
+use serde::Deserialize;
+use serde_json::from_value;
+use glfw::Key;
+use game_engine::graphics::Context;
+use std::ops::Deref;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering::{Release, Acquire, AcqRel, Relaxed};
+use test_harness::cli::args;
+use test_harness::paths::{artifacts_dir, log_dir};
+use test_harness::artifacts::describe_input;
+use test_harness::recording::InputRecording;
+use test_harness::stats::FrameStats;
+use test_harness::projection::screen_projection;
+use test_harness::rng::SharedRng;
+use rand::Rng;
+use test_harness::context::{TestContext, report};
+use test_harness::baseline::{frame_time_gate, BASELINES_DIR, FRAME_TIME_GATE_CHECKS};
+use test_harness::soak::{SoakMonitor, SOAK_CHECKS, SOAK_FRAME_WINDOW};
+use test_harness::cycle::SceneCycler;
+use test_harness::leak::{LeakCheck, ResourceCounts};
+use std::path::PathBuf;
+
+const STRESS_TEST_ID: &str = "stress_test";
+const STRESS_TEST_SCENE_ID: &str = "stress_test_scene";
+
+const SCREEN_WIDTH: f32 = 960.0;
+const SCREEN_HEIGHT: f32 = 540.0;
+/// Radians every sprite turns per update.
+const ROTATION_PER_FRAME: f32 = 0.01;
+
+fn main() -> Result<(), GameLoopError> {
+    args().enter_working_dir().expect("Failed to find the workspace assets");
+
+    let app_name = concat!(env!("CARGO_PKG_NAME"), "-", env!("CARGO_PKG_VERSION")).to_string();
+    let file_appender = tracing_appender::rolling::never(log_dir(env!("CARGO_PKG_NAME")), "stress_test.log");
+    let (non_blocking_writer, _guard) = non_blocking(file_appender);
+
+    let bunyan_formatting_layer = BunyanFormattingLayer::new(app_name, non_blocking_writer);
+    let subscriber = Registry::default()
+        .with(args().env_filter())
+        .with(JsonStorageLayer)
+        .with(bunyan_formatting_layer);
+
+    tracing::subscriber::set_global_default(subscriber).expect("Failed to set global default subscriber");
+
+    let game_loop: GameLoop<StressTestGameWrapper, MultiInput> = GameLoop::new();
+    game_loop.run(STRESS_TEST_ID.to_string())?;
+
+    if !report() {
+        drop(_guard);
+        std::process::exit(1);
+    }
+
+    Ok(())
+}
+
+struct StressTestGameWrapper;
+
+impl StressTestGameWrapper {
+    fn scene_factory(json: JSONLoad) -> Result<Box<dyn SceneLoader<MultiInput>>> {
+        match json.load_type_id.as_str() {
+            STRESS_TEST_SCENE_ID => Ok(Box::new(StressTestSceneLoader::new(from_value(json.actual_value)?))),
+            _ => {Err(Error::msg("Load ID did not match any scene ID"))}
+        }
+    }
+}
+
+impl GameWrapper<MultiInput> for StressTestGameWrapper {
+    fn register_components(ecs: &mut World) {
+        ecs.register::<TextureHandle>();
+        ecs.register::<Transform>();
+    }
+
+    fn load() -> GenTask<SceneStack<MultiInput>> {
+        let ss_loader = SceneStackLoader::new(
+            args().asset_path(STRESS_TEST_ID, &[SCENES_DIR], &args().scene),
+            StressTestGameWrapper::scene_factory
+        );
+
+        let td_loader = TextureDictLoader::new(
+            args().asset_path(STRESS_TEST_ID, &[], TEXTURE_DICT_LOAD_ID)
+        );
+
+        let camera_loader = OrthographicCameraLoader::new(
+            args().asset_path(STRESS_TEST_ID, &[], ORTHOGRAPHIC_CAMERA_LOAD_ID)
+        );
+
+        let td_task = td_loader.load()
+            .map(|texture_dict, ecs| {
+                ecs
+                    .write()
+                    .expect("Failed to lock World")
+                    .insert(texture_dict);
+
+                Ok(())
+            });
+
+        let camera_task = camera_loader.load()
+            .map(|camera, ecs| {
+                ecs.write()
+                    .expect("Failed to acquire write lock for World")
+                    .insert(Some(Box::new(camera) as Box<dyn Camera>));
+
+                Ok(())
+            });
+
+        td_task.join(camera_task, |_| {})
+            .map(|_, ecs| {
+                let rng = SharedRng::from_args(args());
+
+                // Printed before the scene loads, so a run that hangs or crashes can still be replayed with --seed
+                println!("[{}] seed {}", STRESS_TEST_ID, rng.seed());
+                tracing::info!(seed = rng.seed(), "stress test starting");
+
+                ecs.write()
+                    .expect("Failed to acquire write lock for World")
+                    .insert(rng);
+
+                Ok(())
+            })
+            .sequence(ss_loader.load())
+    }
+}
+
+pub struct StressTestScene {
+    sprite_renderer: RefCell<SpriteRenderer>,
+    entity_count: usize,
+    seed: u64,
+    frame_stats: FrameStats,
+    should_finish: AtomicBool,
+    checks_run: AtomicBool,
+    soak: Option<Arc<SoakMonitor>>,
+    recording: Option<InputRecording>,
+    cycler: Option<SceneCycler<StressTestSceneLoader>>,
+    leak_check: LeakCheck,
+    test_context: Option<Arc<TestContext>>
+}
+
+// SpriteRenderer holds GL objects, so the scene is only ever used from the thread that owns the
+// GL context; its RefCell relies on that to skip locking.
+unsafe impl Send for StressTestScene {}
+
+unsafe impl Sync for StressTestScene {}
+
+impl Debug for StressTestScene {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Stress Test Scene")
+            .field("Sprite Renderer", &self.sprite_renderer.borrow().render_state)
+            .field("Entity Count", &self.entity_count)
+            .finish()
+    }
+}
+
+impl Scene<MultiInput> for StressTestScene {
+    fn update(&self, ecs: Arc<RwLock<World>>) -> Result<SceneTransition<MultiInput>> {
+        let ecs = self.frame_stats.timed(|| ecs.read()).expect("Failed to acquire read lock for World");
+        let transforms: ReadStorage<Transform> = ecs.system_data();
+
+        // Touches every sprite each frame, so frame times include ECS iteration and not just drawing
+        for transform in (&transforms).join() {
+            let rotation = (transform.rotation.load(Relaxed) + ROTATION_PER_FRAME) % std::f32::consts::TAU;
+            transform.rotation.store(rotation, Relaxed);
+        }
+
+        match &self.cycler {
+            Some(cycler) => Ok(cycler.update(|cycle| {
+                if let (true, Some(test_context)) = (args().leak_cycles.is_some(), &self.test_context) {
+                    self.leak_check.record(cycle, ResourceCounts::of(ecs.deref()), test_context);
+                }
+            })),
+            None => Ok(SceneTransition::NONE)
+        }
+    }
+
+    fn draw(&self, ecs: Arc<RwLock<World>>) -> Result<()> {
+        self.frame_stats.tick();
+
+        let ecs = self.frame_stats.timed(|| ecs.read()).expect("Failed to acquire read lock for World");
+
+        if let Some(soak) = &self.soak {
+            soak.tick(ecs.deref());
+        }
+
+        if args().headless {
+            if let Some(test_context) = &self.test_context {
+                test_context.capture_if_failed(ecs.deref(), false)?;
+            }
+
+            return Ok(())
+        }
+
+        let context = ecs.fetch::<Context>();
+
+        let mut context = self.frame_stats.timed(|| context.0.write())
+            .expect("Failed to acquire write lock for Context");
+
+        let back_buffer = context.back_buffer()
+            .expect("Failed to get back buffer");
+
+        context.new_pipeline_gate()
+            .pipeline::<SpriteRenderError, Dim2, (), (), _>(
+                &back_buffer,
+                &PipelineState::default().set_clear_color([0.0, 0.0, 0.0, 1.0]),
+                |pipeline, mut shading_gate| {
+                    self.sprite_renderer.borrow_mut()
+                        .render(
+                            &pipeline,
+                            &mut shading_gate,
+                            &screen_projection(SCREEN_WIDTH, SCREEN_HEIGHT),
+                            ecs.deref()
+                        ).unwrap();
+
+                    Ok(())
+                }
+            );
+
+        if let Some(test_context) = &self.test_context {
+            test_context.capture_if_failed(ecs.deref(), true)?;
+        }
+
+        Ok(())
+    }
+
+    fn interact(&self, _ecs: Arc<RwLock<World>>, input: &MultiInput) -> Result<()> {
+        let frame = describe_input(self.frame_stats.frames() as u64, input);
+        if let Some(recording) = &self.recording {
+            recording.record(&frame)?;
+        }
+        if let Some(test_context) = &self.test_context {
+            test_context.record_input(frame);
+        }
+
+        for key in input.get_pressed_keys() {
+            if let Key::Q = key.key {
+                self.should_finish.store(true, Release)
+            }
+        }
+
+        Ok(())
+    }
+
+    fn get_name(&self) -> String {
+        String::from("Stress Test Scene")
+    }
+
+    fn is_finished(&self, _ecs: Arc<RwLock<World>>) -> Result<bool> {
+        let frames_done = args().frames
+            .map_or(false, |frames| self.frame_stats.frames() as u64 >= frames);
+        let soak_done = self.soak.as_ref()
+            .map_or(false, |soak| soak.is_done());
+        let leak_done = self.cycler.as_ref()
+            .map_or(false, |cycler| cycler.is_done());
+        let run_done = frames_done || soak_done || leak_done;
+
+        if let Some(test_context) = &self.test_context {
+            if run_done && !self.checks_run.swap(true, AcqRel) {
+                if args().perf_gate {
+                    let baseline_id = format!("{}_{}", args().scene, self.entity_count);
+                    let baseline_path = PathBuf::from(args().asset_path(STRESS_TEST_ID, &[BASELINES_DIR], &baseline_id));
+                    frame_time_gate(&baseline_path, &self.frame_stats.summary(), test_context, args().update_baseline)?;
+                }
+
+                if let Some(soak) = &self.soak {
+                    soak.check(test_context);
+                }
+            }
+        }
+
+        let checks_done = self.test_context.as_ref()
+            .map_or(true, |test_context| test_context.is_complete());
+
+        let finished = self.should_finish.load(Acquire) || (run_done && checks_done);
+        if finished {
+            println!("[{}] {} entities, seed {}: {}", STRESS_TEST_SCENE_ID, self.entity_count, self.seed, self.frame_stats.summary());
+        }
+
+        return Ok(finished)
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct StressTestSceneJSON {
+    template_path: String,
+    entity_count: usize
+}
+
+/// Spawns `entity_count` copies of the template entity, each with a random position and rotation.
+#[derive(Debug)]
+pub struct StressTestSceneLoader {
+    json: StressTestSceneJSON,
+}
+
+impl StressTestSceneLoader {
+    pub fn new(json: StressTestSceneJSON) -> Self {
+        Self {
+            json
+        }
+    }
+}
+
+/// Gives every Transform a random position and rotation. Entities are visited in entity order, so
+/// the layout only depends on the seed and not on the order the component loaders ran in.
+fn place_entities(ecs: &World) {
+    let mut rng = ecs.fetch::<SharedRng>().fork();
+    let (entities, transforms): (Entities, ReadStorage<Transform>) = ecs.system_data();
+
+    for (_, transform) in (&entities, &transforms).join() {
+        transform.translation[0].store(rng.gen_range(0.0..SCREEN_WIDTH), Relaxed);
+        transform.translation[1].store(rng.gen_range(0.0..SCREEN_HEIGHT), Relaxed);
+        transform.rotation.store(rng.gen_range(0.0..std::f32::consts::TAU), Relaxed);
+    }
+}
+
+impl ComponentMux for StressTestSceneLoader {
+    fn map_json_to_loader(json: JSONLoad) -> Result<Box<dyn ComponentLoader>> {
+        match json.load_type_id.as_str() {
+            TEXTURE_LOAD_ID => Ok(Box::new(TextureLoader::from_json(json)?)),
+            TRANSFORM_LOAD_ID => Ok(Box::new(TransformLoader::from_json(json)?)),
+            _ => Err(Error::msg("Invalid json load ID"))
+        }
+    }
+}
+
+impl SceneLoader<MultiInput> for StressTestSceneLoader {
+    fn load_scene(&self) -> GenTask<Box<dyn Scene<MultiInput>>> {
+        let entity_count = args().entity_count.unwrap_or(self.json.entity_count);
+        let entity_paths = vec![self.json.template_path.clone(); entity_count];
+        let scene_entity_paths = entity_paths.clone();
+        SpriteRendererLoader::load_default()
+            .serialize(
+                Task::new(move |(renderer, ecs): (SpriteRenderer, Arc<RwLock<World>>)| {
+                    create_entity_vec::<Self>(&entity_paths, ecs.clone())?;
+                    place_entities(&ecs.read().expect("Failed to acquire read lock for World"));
+                    return Ok(renderer)
+                })
+            )
+            .map(move |renderer, ecs| {
+                let mut checks = 0;
+                if args().perf_gate {
+                    checks += FRAME_TIME_GATE_CHECKS;
+                }
+                if args().soak.is_some() {
+                    checks += SOAK_CHECKS;
+                }
+                if let Some(cycles) = args().leak_cycles {
+                    checks += cycles as usize;
+                }
+
+                let soak = args().soak.map(|hours| Arc::new(SoakMonitor::new(hours)));
+
+                Ok(Box::new(StressTestScene {
+                    sprite_renderer: RefCell::new(renderer),
+                    entity_count,
+                    seed: ecs.read()
+                        .expect("Failed to acquire read lock for World")
+                        .fetch::<SharedRng>()
+                        .seed(),
+                    frame_stats: match args().soak {
+                        Some(_) => FrameStats::bounded(SOAK_FRAME_WINDOW),
+                        None => FrameStats::new()
+                    },
+                    should_finish: AtomicBool::new(false),
+                    checks_run: AtomicBool::new(false),
+                    soak: soak.clone(),
+                    recording: args().record.as_deref().map(InputRecording::create).transpose()?,
+                    cycler: match (args().leak_cycles, soak) {
+                        (Some(cycles), _) => Some(SceneCycler::new(scene_entity_paths.clone(), Some(cycles))),
+                        (None, Some(soak)) => Some(SceneCycler::soak(scene_entity_paths.clone(), soak)),
+                        (None, None) => None
+                    },
+                    leak_check: LeakCheck::new(),
+                    test_context: match checks {
+                        0 => None,
+                        checks => Some(TestContext::register(
+                            STRESS_TEST_SCENE_ID,
+                            artifacts_dir(env!("CARGO_PKG_NAME"), &args().scene),
+                            checks
+                        )?)
+                    }
+                }) as Box<dyn Scene<MultiInput>>)
+            })
+    }
+}
@@ -0,0 +1,134 @@
+use criterion::{criterion_group, criterion_main, Criterion, BenchmarkId, BatchSize, Throughput, black_box};
+use game_engine::load::{JSONLoad, load_deserializable_from_file, create_entity_vec};
+use game_engine::components::{ComponentMux, ComponentLoader};
+use game_engine::graphics::texture::{TextureLoader, TEXTURE_LOAD_ID};
+use game_engine::graphics::transform::{Transform, TransformLoader, TRANSFORM_LOAD_ID};
+use game_engine::camera::orthographic_camera::OrthographicCameraLoader;
+use game_engine::loading::GenTask;
+use anyhow::{Result, Error};
+use serde::Deserialize;
+use serde_json::from_str;
+use specs::{World, WorldExt};
+use std::fs::read_to_string;
+use std::sync::{Arc, RwLock};
+use test_harness::paths::enter_workspace_root;
+
+const ENTITY_LOAD_ID: &str = "entity_loader";
+const ENTITY_FIXTURE: &str = "assets/JSON/bench/entities/bench_entity.json";
+/// Entities with only a Transform, which can be created without a GL context.
+const TRANSFORM_ENTITY_FIXTURE: &str = "assets/JSON/bench/entities/bench_transform_entity.json";
+const ENTITY_COUNTS: [usize; 3] = [1, 10, 100];
+/// Loading a camera touches no GL state, so it stands in for a task node with real file I/O.
+const CAMERA_FIXTURE: &str = "assets/JSON/camera_test/orthographic_camera.json";
+const TASK_COUNTS: [usize; 3] = [1, 10, 100];
+
+#[derive(Deserialize, Debug)]
+struct EntityJSON {
+    component_paths: Vec<String>
+}
+
+struct BenchMux;
+
+impl ComponentMux for BenchMux {
+    fn map_json_to_loader(json: JSONLoad) -> Result<Box<dyn ComponentLoader>> {
+        match json.load_type_id.as_str() {
+            TEXTURE_LOAD_ID => Ok(Box::new(TextureLoader::from_json(json)?)),
+            TRANSFORM_LOAD_ID => Ok(Box::new(TransformLoader::from_json(json)?)),
+            _ => Err(Error::msg("Invalid json load ID"))
+        }
+    }
+}
+
+fn new_world() -> Arc<RwLock<World>> {
+    let mut ecs = World::new();
+    ecs.register::<Transform>();
+
+    Arc::new(RwLock::new(ecs))
+}
+
+fn entity_from_file(c: &mut Criterion) {
+    // The paths inside the fixtures are relative to the workspace root, like the test binaries'
+    enter_workspace_root().expect("Failed to find the workspace assets");
+
+    c.bench_function("load_deserializable_from_file", |b| {
+        b.iter(|| load_deserializable_from_file::<EntityJSON>(black_box(ENTITY_FIXTURE), ENTITY_LOAD_ID).unwrap())
+    });
+}
+
+fn component_from_json(c: &mut Criterion) {
+    enter_workspace_root().expect("Failed to find the workspace assets");
+    let entity = load_deserializable_from_file::<EntityJSON>(ENTITY_FIXTURE, ENTITY_LOAD_ID).unwrap();
+
+    let mut group = c.benchmark_group("component_from_json");
+    for component_path in entity.component_paths {
+        let json = read_to_string(&component_path).unwrap();
+        let load_type_id = from_str::<JSONLoad>(&json).unwrap().load_type_id;
+
+        group.bench_with_input(BenchmarkId::from_parameter(load_type_id), &json, |b, json| {
+            b.iter(|| BenchMux::map_json_to_loader(from_str(black_box(json)).unwrap()).unwrap())
+        });
+    }
+    group.finish();
+}
+
+fn entity_creation(c: &mut Criterion) {
+    enter_workspace_root().expect("Failed to find the workspace assets");
+
+    let mut group = c.benchmark_group("create_entity_vec");
+    for count in ENTITY_COUNTS.iter() {
+        let entity_paths = vec![TRANSFORM_ENTITY_FIXTURE.to_string(); *count];
+
+        group.throughput(Throughput::Elements(*count as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(count), &entity_paths, |b, entity_paths| {
+            b.iter_batched(
+                new_world,
+                |ecs| create_entity_vec::<BenchMux>(entity_paths, ecs).unwrap(),
+                BatchSize::SmallInput
+            )
+        });
+    }
+    group.finish();
+}
+
+fn camera_task() -> GenTask<()> {
+    OrthographicCameraLoader::new(CAMERA_FIXTURE.to_string())
+        .load()
+        .map(|camera, _ecs| {
+            black_box(camera);
+            Ok(())
+        })
+}
+
+/// Joins `count` camera loads, the way `GameWrapper::load` joins the texture dict and camera.
+fn joined_tasks(count: usize) -> GenTask<()> {
+    (1..count).fold(camera_task(), |task, _| task.join(camera_task(), |_| {}))
+}
+
+/// Runs `count` camera loads one after another, the way `GameWrapper::load` runs the scene stack.
+fn sequenced_tasks(count: usize) -> GenTask<()> {
+    (1..count).fold(camera_task(), |task, _| task.sequence(camera_task()))
+}
+
+// Sprite submission and texture upload need the GL context that only GameLoop creates, so the
+// renderer can't be benchmarked from here. Task graphs are measured through GenTask::run instead.
+fn task_graph(c: &mut Criterion) {
+    enter_workspace_root().expect("Failed to find the workspace assets");
+
+    for (name, build) in [("task_join", joined_tasks as fn(usize) -> GenTask<()>), ("task_sequence", sequenced_tasks)].iter() {
+        let mut group = c.benchmark_group(*name);
+        for count in TASK_COUNTS.iter() {
+            group.throughput(Throughput::Elements(*count as u64));
+            group.bench_with_input(BenchmarkId::from_parameter(count), count, |b, count| {
+                b.iter_batched(
+                    || (build(*count), new_world()),
+                    |(task, ecs)| task.run(ecs).unwrap(),
+                    BatchSize::SmallInput
+                )
+            });
+        }
+        group.finish();
+    }
+}
+
+criterion_group!(benches, entity_from_file, component_from_json, entity_creation, task_graph);
+criterion_main!(benches);
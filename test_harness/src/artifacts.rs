@@ -0,0 +1,160 @@
+use std::collections::VecDeque;
+use std::ffi::CString;
+use std::fs::{create_dir_all, remove_file, write};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering::SeqCst;
+use specs::{World, WorldExt, Join};
+use glfw::Key;
+use game_engine::camera::Camera;
+use game_engine::input::multi_input::MultiInput;
+
+pub const ARTIFACTS_DIR: &str = "artifacts";
+pub const INPUT_HISTORY_LEN: usize = 120;
+
+const REASON_FILE: &str = "reason.txt";
+const INPUT_FILE: &str = "input.txt";
+const WORLD_FILE: &str = "world.txt";
+const SCREENSHOT_FILE: &str = "screenshot.png";
+
+/// One line of input history, as written to `input.txt`.
+pub fn describe_input(frame: u64, input: &MultiInput) -> String {
+    let pressed: Vec<Key> = input.get_pressed_keys().into_iter().map(|key| key.key).collect();
+    let held: Vec<Key> = input.get_held_keys().into_iter().map(|key| key.key).collect();
+
+    format!("frame {}: pressed {:?} held {:?}", frame, pressed, held)
+}
+
+/// Keeps what is needed to reproduce a failing test run and writes it to `dir` (normally
+/// `artifacts/<crate>/<scene>/`) the first time `capture` is called or a panic happens.
+#[derive(Debug)]
+pub struct FailureArtifacts {
+    dir: PathBuf,
+    input_history: Mutex<VecDeque<String>>,
+    captured: AtomicBool
+}
+
+impl FailureArtifacts {
+    /// Removes artifacts left in `dir` by an earlier run, so a passing run never shows an old failure.
+    /// Only the files written here are removed; the test runner keeps its output in the same directory.
+    pub fn new(dir: PathBuf) -> io::Result<Arc<Self>> {
+        for file in [REASON_FILE, INPUT_FILE, WORLD_FILE, SCREENSHOT_FILE].iter() {
+            match remove_file(dir.join(file)) {
+                Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e),
+                _ => {}
+            }
+        }
+
+        Ok(Arc::new(Self {
+            dir,
+            input_history: Mutex::new(VecDeque::with_capacity(INPUT_HISTORY_LEN)),
+            captured: AtomicBool::new(false)
+        }))
+    }
+
+    /// Records one frame of input, keeping only the last `INPUT_HISTORY_LEN` frames.
+    pub fn record_input(&self, frame: String) {
+        let mut input_history = self.input_history.lock()
+            .expect("Failed to lock input history");
+
+        if input_history.len() == INPUT_HISTORY_LEN {
+            input_history.pop_front();
+        }
+        input_history.push_back(frame);
+    }
+
+    /// Writes the input history and panic message when a panic happens. The World and GL context
+    /// are not reachable from a panic hook, so there is no screenshot or World summary in this case.
+    pub fn install_panic_hook(self: &Arc<Self>) {
+        let artifacts = self.clone();
+        let default_hook = std::panic::take_hook();
+
+        std::panic::set_hook(Box::new(move |info| {
+            if !artifacts.captured.swap(true, SeqCst) {
+                let _ = artifacts.write_common(&info.to_string());
+            }
+            default_hook(info);
+        }));
+    }
+
+    /// Writes the input history, World summary, and optionally a screenshot. Screenshots must be
+    /// taken from `Scene::draw` after rendering and while the Context is locked, so the back buffer
+    /// is still readable.
+    pub fn capture(&self, ecs: &World, reason: &str, screenshot: bool) -> io::Result<()> {
+        if self.captured.swap(true, SeqCst) {
+            return Ok(())
+        }
+
+        self.write_common(reason)?;
+        write(self.dir.join(WORLD_FILE), world_summary(ecs))?;
+
+        if screenshot {
+            save_screenshot(&self.dir.join(SCREENSHOT_FILE))?;
+        }
+
+        Ok(())
+    }
+
+    pub fn is_captured(&self) -> bool {
+        self.captured.load(SeqCst)
+    }
+
+    fn write_common(&self, reason: &str) -> io::Result<()> {
+        create_dir_all(&self.dir)?;
+        write(self.dir.join(REASON_FILE), reason)?;
+
+        let input_history = self.input_history.lock()
+            .expect("Failed to lock input history");
+        let input: Vec<&str> = input_history.iter().map(String::as_str).collect();
+        write(self.dir.join(INPUT_FILE), input.join("\n"))
+    }
+}
+
+fn world_summary(ecs: &World) -> String {
+    let mut summary = vec![format!("entities: {}", ecs.entities().join().count())];
+
+    if let Some(camera) = ecs.try_fetch::<Option<Box<dyn Camera>>>() {
+        if let Some(camera) = &*camera {
+            summary.push(format!("camera position: {:?}", camera.position()));
+            summary.push(format!("camera target: {:?}", camera.target()));
+        }
+    }
+
+    summary.join("\n")
+}
+
+/// Reads the current viewport of the back buffer and saves it as a PNG.
+fn save_screenshot(path: &Path) -> io::Result<()> {
+    gl::load_with(|symbol| {
+        let symbol = CString::new(symbol).expect("GL symbol contained a nul byte");
+        unsafe { glfw::ffi::glfwGetProcAddress(symbol.as_ptr()) as *const _ }
+    });
+
+    let mut viewport = [0; 4];
+    unsafe { gl::GetIntegerv(gl::VIEWPORT, viewport.as_mut_ptr()) };
+    let (width, height) = (viewport[2] as u32, viewport[3] as u32);
+
+    let mut pixels = vec![0u8; (width * height * 4) as usize];
+    unsafe {
+        gl::ReadBuffer(gl::BACK);
+        gl::ReadPixels(
+            viewport[0],
+            viewport[1],
+            width as i32,
+            height as i32,
+            gl::RGBA,
+            gl::UNSIGNED_BYTE,
+            pixels.as_mut_ptr() as *mut _
+        );
+    }
+
+    let image = image::RgbaImage::from_raw(width, height, pixels)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "Screenshot buffer had the wrong size"))?;
+
+    // GL rows start at the bottom of the screen
+    image::imageops::flip_vertical(&image)
+        .save(path)
+        .map_err(|e| io::Error::new(io::ErrorKind::Other, e))
+}
@@ -0,0 +1,114 @@
+use serde::{Deserialize, Serialize};
+use std::fs::{create_dir_all, read_to_string, write};
+use std::io;
+use std::path::Path;
+use crate::context::TestContext;
+use crate::stats::FrameSummary;
+
+pub const BASELINES_DIR: &str = "baselines";
+pub const DEFAULT_TOLERANCE: f64 = 0.2;
+/// Number of checks `frame_time_gate` records.
+pub const FRAME_TIME_GATE_CHECKS: usize = 1;
+
+/// Checked-in frame time for a scenario. A run fails when its p95 frame time is more than
+/// `tolerance` (as a fraction) above `p95_ms`.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct FrameBaseline {
+    pub p95_ms: f64,
+    pub tolerance: f64
+}
+
+impl FrameBaseline {
+    pub fn load(path: &Path) -> io::Result<Self> {
+        serde_json::from_str(&read_to_string(path)?)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(dir) = path.parent() {
+            create_dir_all(dir)?;
+        }
+
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        write(path, json)
+    }
+
+    pub fn check(&self, summary: &FrameSummary, test_context: &TestContext) {
+        let p95_ms = summary.p95.as_secs_f64() * 1000.0;
+        let limit_ms = self.p95_ms * (1.0 + self.tolerance);
+
+        test_context.assert(
+            p95_ms <= limit_ms,
+            format!("p95 frame time {:.2}ms, baseline {:.2}ms, limit {:.2}ms", p95_ms, self.p95_ms, limit_ms)
+        );
+    }
+}
+
+/// Checks `summary` against the baseline at `path`, or overwrites the baseline with it when `update` is set.
+/// A missing baseline fails the check rather than the run, so the report says how to create one.
+pub fn frame_time_gate(path: &Path, summary: &FrameSummary, test_context: &TestContext, update: bool) -> io::Result<()> {
+    if update {
+        let tolerance = FrameBaseline::load(path)
+            .map_or(DEFAULT_TOLERANCE, |baseline| baseline.tolerance);
+
+        let baseline = FrameBaseline {
+            p95_ms: summary.p95.as_secs_f64() * 1000.0,
+            tolerance
+        };
+        baseline.save(path)?;
+        test_context.assert(true, format!("Updated baseline to {:.2}ms", baseline.p95_ms));
+
+        return Ok(())
+    }
+
+    match FrameBaseline::load(path) {
+        Ok(baseline) => baseline.check(summary, test_context),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => test_context.assert(
+            false,
+            format!("No baseline at {}; record one with --update-baseline", path.display())
+        ),
+        Err(e) => return Err(e)
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::tests::test_context;
+    use std::time::Duration;
+
+    fn summary(p95_ms: u64) -> FrameSummary {
+        let p95 = Duration::from_millis(p95_ms);
+
+        FrameSummary {
+            frames: 100,
+            mean: p95,
+            p50: p95,
+            p95,
+            max: p95,
+            lock_wait_mean: Duration::default(),
+            lock_wait_p95: Duration::default()
+        }
+    }
+
+    #[test]
+    fn check_passes_within_tolerance() {
+        let baseline = FrameBaseline { p95_ms: 10.0, tolerance: 0.2 };
+        let context = test_context("within_tolerance", 1);
+
+        baseline.check(&summary(12), &context);
+        assert!(context.passed());
+    }
+
+    #[test]
+    fn check_fails_over_tolerance() {
+        let baseline = FrameBaseline { p95_ms: 10.0, tolerance: 0.2 };
+        let context = test_context("over_tolerance", 1);
+
+        baseline.check(&summary(13), &context);
+        assert!(!context.passed());
+    }
+}
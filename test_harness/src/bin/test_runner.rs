@@ -0,0 +1,334 @@
+use clap::Parser;
+use serde_json::{json, Value};
+use std::fs::{create_dir_all, read_dir, read_to_string, remove_dir_all, write, File};
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitStatus, Stdio};
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+use game_engine::load::LOAD_PATH;
+use game_engine::scenes::SCENES_DIR;
+use test_harness::artifacts::ARTIFACTS_DIR;
+use test_harness::paths::{artifacts_dir, workspace_root};
+
+const SCENE_STACK_LOAD_ID: &str = "scene_stack";
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Runs every test scenario in the workspace and writes JUnit XML and JSON reports.
+#[derive(Parser, Debug)]
+struct RunnerArgs {
+    /// Frames each scenario runs for
+    #[clap(long, default_value = "300")]
+    frames: u64,
+    /// Seconds before a scenario is killed and reported as failed
+    #[clap(long, default_value = "60")]
+    timeout: u64,
+    /// Only run scenarios whose name contains this string
+    #[clap(long)]
+    filter: Option<String>,
+    /// Draw scenes instead of passing --headless
+    #[clap(long)]
+    windowed: bool,
+}
+
+/// A scene stack file belonging to a test crate, run as `<crate> --scene <scene>`.
+#[derive(Debug, Clone)]
+struct Scenario {
+    test_crate: String,
+    scene: String
+}
+
+impl Scenario {
+    fn name(&self) -> String {
+        [self.test_crate.as_str(), "::", self.scene.as_str()].concat()
+    }
+}
+
+#[derive(Debug)]
+enum Outcome {
+    Passed,
+    Failed(ExitStatus),
+    TimedOut
+}
+
+#[derive(Debug)]
+struct ScenarioResult {
+    scenario: Scenario,
+    outcome: Outcome,
+    duration: Duration,
+    output: String
+}
+
+/// A scenario exists for every `assets/JSON/<crate>/scenes/*.json` scene stack whose `<crate>` is a workspace member.
+fn discover_scenarios(root: &Path) -> Vec<Scenario> {
+    let mut scenarios = Vec::new();
+
+    let test_dirs = match read_dir(root.join(LOAD_PATH)) {
+        Ok(dirs) => dirs,
+        Err(_) => return scenarios
+    };
+
+    for test_dir in test_dirs.filter_map(|entry| entry.ok()).map(|entry| entry.path()) {
+        let test_crate = match test_dir.file_name().and_then(|name| name.to_str()) {
+            Some(name) if root.join(name).join("Cargo.toml").is_file() => name.to_string(),
+            _ => continue
+        };
+
+        let scene_files = match read_dir(test_dir.join(SCENES_DIR)) {
+            Ok(files) => files,
+            Err(_) => continue
+        };
+
+        for scene_file in scene_files.filter_map(|entry| entry.ok()).map(|entry| entry.path()) {
+            let is_scene_stack = read_to_string(&scene_file).ok()
+                .and_then(|json| serde_json::from_str::<Value>(&json).ok())
+                .map_or(false, |json| json["load_type_id"] == SCENE_STACK_LOAD_ID);
+
+            if let (true, Some(scene)) = (is_scene_stack, scene_file.file_stem().and_then(|stem| stem.to_str())) {
+                scenarios.push(Scenario {
+                    test_crate: test_crate.clone(),
+                    scene: scene.to_string()
+                });
+            }
+        }
+    }
+
+    scenarios.sort_by_key(Scenario::name);
+    scenarios
+}
+
+/// Builds every test binary up front so that compile time doesn't count against scenario timeouts.
+fn build_workspace() -> std::io::Result<()> {
+    let status = Command::new("cargo")
+        .current_dir(workspace_root())
+        .args(&["build", "--workspace"])
+        .status()?;
+
+    if status.success() {
+        Ok(())
+    } else {
+        Err(std::io::Error::new(std::io::ErrorKind::Other, format!("cargo build exited with {}", status)))
+    }
+}
+
+/// Debug build of a test crate's binary, as produced by `build_workspace`.
+fn test_binary(test_crate: &str) -> PathBuf {
+    // A relative CARGO_TARGET_DIR is relative to where the runner was started, like it is for cargo
+    let target_dir = std::env::var_os("CARGO_TARGET_DIR")
+        .map_or_else(|| workspace_root().join("target"), |dir| {
+            std::env::current_dir().expect("Failed to read the current directory").join(dir)
+        });
+
+    target_dir.join("debug").join([test_crate, std::env::consts::EXE_SUFFIX].concat())
+}
+
+fn run_scenario(scenario: &Scenario, args: &RunnerArgs, output_dir: &Path) -> std::io::Result<ScenarioResult> {
+    create_dir_all(output_dir)?;
+    let output_path = output_dir.join("output.txt");
+    let output = File::create(&output_path)?;
+
+    let mut command = Command::new(test_binary(&scenario.test_crate));
+    command
+        .current_dir(workspace_root())
+        .args(&["--scene", &scenario.scene, "--frames", &args.frames.to_string()])
+        .stdout(Stdio::from(output.try_clone()?))
+        .stderr(Stdio::from(output));
+
+    if !args.windowed {
+        command.arg("--headless");
+    }
+
+    let start = Instant::now();
+    let timeout = Duration::from_secs(args.timeout);
+    let mut child = command.spawn()?;
+
+    let outcome = loop {
+        if let Some(status) = child.try_wait()? {
+            break if status.success() { Outcome::Passed } else { Outcome::Failed(status) }
+        }
+
+        if start.elapsed() >= timeout {
+            child.kill()?;
+            child.wait()?;
+            break Outcome::TimedOut
+        }
+
+        sleep(POLL_INTERVAL);
+    };
+
+    Ok(ScenarioResult {
+        scenario: scenario.clone(),
+        outcome,
+        duration: start.elapsed(),
+        output: read_to_string(output_path).unwrap_or_default()
+    })
+}
+
+/// Characters XML 1.0 does not allow anywhere in a document, even escaped.
+fn is_invalid_xml_char(c: char) -> bool {
+    (c < '\u{20}' && !matches!(c, '\t' | '\n' | '\r')) || c == '\u{FFFE}' || c == '\u{FFFF}'
+}
+
+/// Escapes `text` for attributes and text nodes. Control characters, such as the ESC of ANSI colour
+/// codes in test output, are dropped because they would make the report unparseable.
+fn escape_xml(text: &str) -> String {
+    text.replace(is_invalid_xml_char, "")
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn junit_report(results: &[ScenarioResult]) -> String {
+    let failures = results.iter().filter(|result| !matches!(result.outcome, Outcome::Passed)).count();
+    let total_time: f64 = results.iter().map(|result| result.duration.as_secs_f64()).sum();
+
+    let mut xml = vec![
+        r#"<?xml version="1.0" encoding="UTF-8"?>"#.to_string(),
+        format!(r#"<testsuite name="GameEngineTests" tests="{}" failures="{}" time="{:.3}">"#, results.len(), failures, total_time)
+    ];
+
+    for result in results {
+        xml.push(format!(
+            r#"  <testcase classname="{}" name="{}" time="{:.3}">"#,
+            escape_xml(&result.scenario.test_crate),
+            escape_xml(&result.scenario.scene),
+            result.duration.as_secs_f64()
+        ));
+
+        match &result.outcome {
+            Outcome::Passed => {},
+            Outcome::Failed(status) => xml.push(format!(r#"    <failure message="exited with {}"/>"#, status)),
+            Outcome::TimedOut => xml.push(r#"    <failure message="timed out"/>"#.to_string())
+        }
+
+        xml.push(format!("    <system-out>{}</system-out>", escape_xml(&result.output)));
+        xml.push("  </testcase>".to_string());
+    }
+
+    xml.push("</testsuite>".to_string());
+    xml.join("\n")
+}
+
+fn json_report(results: &[ScenarioResult]) -> Value {
+    let scenarios: Vec<Value> = results.iter()
+        .map(|result| json!({
+            "name": result.scenario.name(),
+            "passed": matches!(result.outcome, Outcome::Passed),
+            "outcome": match &result.outcome {
+                Outcome::Passed => "passed".to_string(),
+                Outcome::Failed(status) => format!("exited with {}", status),
+                Outcome::TimedOut => "timed out".to_string()
+            },
+            "seconds": result.duration.as_secs_f64(),
+            "output": result.output
+        }))
+        .collect();
+
+    json!({ "scenarios": scenarios })
+}
+
+fn main() -> std::io::Result<()> {
+    let args = RunnerArgs::parse();
+    let root = workspace_root();
+    let report_dir: PathBuf = root.join(ARTIFACTS_DIR);
+
+    let scenarios: Vec<Scenario> = discover_scenarios(&root).into_iter()
+        .filter(|scenario| args.filter.as_ref().map_or(true, |filter| scenario.name().contains(filter.as_str())))
+        .collect();
+
+    // Artifacts from earlier runs would sit next to this run's report and look like its failures
+    match remove_dir_all(&report_dir) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e),
+        _ => {}
+    }
+
+    build_workspace()?;
+
+    let mut results = Vec::new();
+    for scenario in scenarios.iter() {
+        println!("running {}", scenario.name());
+        let result = run_scenario(scenario, &args, &artifacts_dir(&scenario.test_crate, &scenario.scene))?;
+        println!("{} {:?} ({:.1}s)", scenario.name(), result.outcome, result.duration.as_secs_f64());
+        results.push(result);
+    }
+
+    create_dir_all(&report_dir)?;
+    write(report_dir.join("report.xml"), junit_report(&results))?;
+    write(report_dir.join("report.json"), serde_json::to_string_pretty(&json_report(&results))?)?;
+
+    let failed = results.iter().filter(|result| !matches!(result.outcome, Outcome::Passed)).count();
+    println!("{} passed, {} failed", results.len() - failed, failed);
+
+    if failed > 0 {
+        std::process::exit(1);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn write_json(path: &Path, load_type_id: &str) {
+        create_dir_all(path.parent().unwrap()).unwrap();
+        write(path, json!({ "load_type_id": load_type_id, "actual_value": {} }).to_string()).unwrap();
+    }
+
+    fn result(scene: &str, outcome: Outcome, output: &str) -> ScenarioResult {
+        ScenarioResult {
+            scenario: Scenario { test_crate: "camera_test".to_string(), scene: scene.to_string() },
+            outcome,
+            duration: Duration::from_millis(1500),
+            output: output.to_string()
+        }
+    }
+
+    #[test]
+    fn discovers_scene_stacks_of_workspace_members() {
+        let root = tempdir().unwrap();
+        let scenes = |test_crate: &str| root.path().join(LOAD_PATH).join(test_crate).join(SCENES_DIR);
+
+        create_dir_all(root.path().join("camera_test")).unwrap();
+        write(root.path().join("camera_test").join("Cargo.toml"), "").unwrap();
+        write_json(&scenes("camera_test").join("scene_stack.json"), SCENE_STACK_LOAD_ID);
+        write_json(&scenes("camera_test").join("second_stack.json"), SCENE_STACK_LOAD_ID);
+        write_json(&scenes("camera_test").join("camera_test_scene.json"), "camera_test_scene");
+        // Assets without a crate of the same name are shared, not a test
+        write_json(&scenes("bench").join("scene_stack.json"), SCENE_STACK_LOAD_ID);
+
+        let names: Vec<String> = discover_scenarios(root.path()).iter().map(Scenario::name).collect();
+        assert_eq!(names, vec!["camera_test::scene_stack", "camera_test::second_stack"]);
+    }
+
+    #[test]
+    fn discovers_nothing_without_assets() {
+        let root = tempdir().unwrap();
+        assert!(discover_scenarios(root.path()).is_empty());
+    }
+
+    #[test]
+    fn escape_xml_escapes_markup() {
+        assert_eq!(escape_xml(r#"<a href="x">&</a>"#), "&lt;a href=&quot;x&quot;&gt;&amp;&lt;/a&gt;");
+    }
+
+    #[test]
+    fn escape_xml_drops_control_characters() {
+        assert_eq!(escape_xml("\u{1b}[31mred\u{1b}[0m\u{0}\tand\r\nmore\u{FFFF}"), "[31mred[0m\tand\r\nmore");
+    }
+
+    #[test]
+    fn junit_report_counts_failures() {
+        let report = junit_report(&[
+            result("scene_stack", Outcome::Passed, "ok"),
+            result("slow_stack", Outcome::TimedOut, "panicked at \u{1b}[1m'x < y'")
+        ]);
+
+        assert!(report.contains(r#"<testsuite name="GameEngineTests" tests="2" failures="1" time="3.000">"#));
+        assert!(report.contains(r#"<testcase classname="camera_test" name="slow_stack" time="1.500">"#));
+        assert!(report.contains(r#"<failure message="timed out"/>"#));
+        assert!(report.contains("<system-out>panicked at [1m'x &lt; y'</system-out>"));
+        assert_eq!(report.matches("<failure").count(), 1);
+    }
+}
@@ -0,0 +1,164 @@
+use clap::Parser;
+use once_cell::sync::OnceCell;
+use tracing_subscriber::EnvFilter;
+use game_engine::load::{LOAD_PATH, JSON_FILE};
+use std::io;
+use std::path::PathBuf;
+use crate::paths::enter_workspace_root;
+use crate::soak::min_soak_hours;
+use game_engine::scenes::scene_stack::SCENE_STACK_FILE_ID;
+
+static ARGS: OnceCell<TestArgs> = OnceCell::new();
+
+/// Command line options shared by every test binary in the workspace.
+#[derive(Parser, Debug, Clone)]
+pub struct TestArgs {
+    /// Scene stack file (without extension) to load from the test's scenes directory
+    #[clap(long, default_value = SCENE_STACK_FILE_ID)]
+    pub scene: String,
+    /// Finish the scene after this many frames instead of waiting for input
+    #[clap(long)]
+    pub frames: Option<u64>,
+    /// Number of entities for scenes that generate their own, overriding the scene JSON
+    #[clap(long)]
+    pub entity_count: Option<usize>,
+    /// Seed for the shared RNG. A random seed is used and printed when not given, except with
+    /// --perf-gate, which always uses the same layout
+    #[clap(long)]
+    pub seed: Option<u64>,
+    /// Skip drawing; the window is still created but nothing is rendered to it
+    #[clap(long)]
+    pub headless: bool,
+    /// Write every frame's input to this file, one line per frame. Recordings can't be replayed
+    /// yet; that needs the engine to accept input from somewhere other than the window
+    #[clap(long, parse(from_os_str))]
+    pub record: Option<PathBuf>,
+    /// Directory the test's JSON assets are loaded from. Defaults to the workspace's assets
+    #[clap(long, parse(from_os_str))]
+    pub asset_root: Option<PathBuf>,
+    /// Fail the run when p95 frame time exceeds the scene's checked-in baseline. Baselines should be
+    /// recorded and checked with vsync off, otherwise every run measures the monitor's refresh rate
+    #[clap(long, requires = "frames", conflicts_with = "headless")]
+    pub perf_gate: bool,
+    /// Overwrite the baseline with this run's frame times instead of checking against it
+    #[clap(long, requires = "perf-gate")]
+    pub update_baseline: bool,
+    /// Keep the scene running for this many hours, pushing and popping a scene that loads the test's
+    /// entities and sampling memory, fps, and entity counts every minute. Must be long enough for
+    /// the growth checks to have enough samples, currently 11 minutes
+    #[clap(long, conflicts_with = "frames", parse(try_from_str = parse_soak_hours))]
+    pub soak: Option<f64>,
+    /// Push and pop a scene that loads the test's entities this many times, failing if entity,
+    /// transform, or texture handle counts don't return to what they were before the first push
+    #[clap(long, conflicts_with_all = &["frames", "soak"], parse(try_from_str = parse_leak_cycles))]
+    pub leak_cycles: Option<u64>,
+    /// Tracing filter directive. Falls back to RUST_LOG when not given
+    #[clap(long)]
+    pub log_level: Option<String>,
+}
+
+fn parse_soak_hours(hours: &str) -> Result<f64, String> {
+    let hours: f64 = hours.parse().map_err(|err| format!("{}", err))?;
+
+    if hours.is_finite() && hours >= min_soak_hours() {
+        Ok(hours)
+    } else {
+        Err(format!("expected at least {:.3} hours, got {}", min_soak_hours(), hours))
+    }
+}
+
+fn parse_leak_cycles(cycles: &str) -> Result<u64, String> {
+    match cycles.parse().map_err(|err| format!("{}", err))? {
+        0 => Err(String::from("expected at least one cycle")),
+        cycles => Ok(cycles)
+    }
+}
+
+impl TestArgs {
+    pub fn asset_root(&self) -> PathBuf {
+        self.asset_root.clone().unwrap_or_else(|| PathBuf::from(LOAD_PATH))
+    }
+
+    /// Moves to the workspace root so the default asset paths resolve, unless `--asset-root` was
+    /// given, in which case the paths are left relative to the directory the test was started from.
+    pub fn enter_working_dir(&self) -> io::Result<()> {
+        match self.asset_root {
+            Some(_) => Ok(()),
+            None => enter_workspace_root()
+        }
+    }
+
+    /// Path of a JSON file under `<asset root>/<test_id>/`, e.g.
+    /// `asset_path(CAMERA_TEST_ID, &[SCENES_DIR], SCENE_STACK_FILE_ID)`.
+    pub fn asset_path(&self, test_id: &str, dirs: &[&str], file_id: &str) -> String {
+        let mut path = self.asset_root().join(test_id);
+        for dir in dirs {
+            path.push(dir);
+        }
+        path.push([file_id, JSON_FILE].concat());
+
+        path.to_string_lossy().into_owned()
+    }
+
+    pub fn env_filter(&self) -> EnvFilter {
+        match &self.log_level {
+            Some(level) => EnvFilter::new(level),
+            None => EnvFilter::from_default_env()
+        }
+    }
+}
+
+/// Parses the process arguments the first time it is called and returns the same values afterwards.
+pub fn args() -> &'static TestArgs {
+    ARGS.get_or_init(TestArgs::parse)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    #[test]
+    fn asset_path_defaults_to_load_path() {
+        let args = TestArgs::try_parse_from(&["test"]).unwrap();
+
+        assert_eq!(
+            Path::new(&args.asset_path("camera_test", &["scenes"], "scene_stack")),
+            Path::new(LOAD_PATH).join("camera_test").join("scenes").join(["scene_stack", JSON_FILE].concat())
+        );
+    }
+
+    #[test]
+    fn asset_path_uses_asset_root() {
+        let args = TestArgs::try_parse_from(&["test", "--asset-root", "root"]).unwrap();
+
+        assert_eq!(
+            Path::new(&args.asset_path("stress_test", &[], "texture_dict")),
+            Path::new("root").join("stress_test").join(["texture_dict", JSON_FILE].concat())
+        );
+    }
+
+    #[test]
+    fn perf_gate_rejects_headless() {
+        assert!(TestArgs::try_parse_from(&["test", "--frames", "10", "--perf-gate"]).is_ok());
+        assert!(TestArgs::try_parse_from(&["test", "--frames", "10", "--perf-gate", "--headless"]).is_err());
+        assert!(TestArgs::try_parse_from(&["test", "--perf-gate"]).is_err());
+    }
+
+    #[test]
+    fn soak_rejects_short_hours() {
+        assert!(TestArgs::try_parse_from(&["test", "--soak", "0"]).is_err());
+        assert!(TestArgs::try_parse_from(&["test", "--soak", "-1"]).is_err());
+        assert!(TestArgs::try_parse_from(&["test", "--soak", "NaN"]).is_err());
+        assert!(TestArgs::try_parse_from(&["test", "--soak", "0.1"]).is_err());
+        assert!(TestArgs::try_parse_from(&["test", "--soak", "0.5"]).is_ok());
+    }
+
+    #[test]
+    fn leak_cycles_rejects_zero_and_other_run_lengths() {
+        assert!(TestArgs::try_parse_from(&["test", "--leak-cycles", "5"]).is_ok());
+        assert!(TestArgs::try_parse_from(&["test", "--leak-cycles", "0"]).is_err());
+        assert!(TestArgs::try_parse_from(&["test", "--leak-cycles", "5", "--frames", "10"]).is_err());
+        assert!(TestArgs::try_parse_from(&["test", "--leak-cycles", "5", "--soak", "1"]).is_err());
+    }
+}
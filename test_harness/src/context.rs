@@ -0,0 +1,188 @@
+use once_cell::sync::Lazy;
+use std::fmt::Debug;
+use std::io;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use specs::World;
+use crate::artifacts::FailureArtifacts;
+
+static CONTEXTS: Lazy<Mutex<Vec<Arc<TestContext>>>> = Lazy::new(|| Mutex::new(Vec::new()));
+
+#[derive(Debug, Clone)]
+pub struct CheckResult {
+    pub passed: bool,
+    pub message: String
+}
+
+/// Collects the checks a scene makes while it runs.
+///
+/// A scene knows how many checks it is going to make up front, so `is_complete` can be used
+/// from `Scene::is_finished` to end the scene once every check has been recorded. When a check
+/// fails the scene should call `capture_if_failed` from `draw` to save failure artifacts.
+#[derive(Debug)]
+pub struct TestContext {
+    pub name: String,
+    expected_checks: usize,
+    results: Mutex<Vec<CheckResult>>,
+    artifacts: Arc<FailureArtifacts>
+}
+
+impl TestContext {
+    /// Creates a context and registers it so `report` can see its results after the game loop exits.
+    /// Failure artifacts go in `artifacts_dir`, normally `paths::artifacts_dir(<crate>, &args().scene)`.
+    pub fn register(name: &str, artifacts_dir: PathBuf, expected_checks: usize) -> io::Result<Arc<Self>> {
+        let context = Self::new(name, artifacts_dir, expected_checks)?;
+        context.artifacts.install_panic_hook();
+
+        CONTEXTS.lock()
+            .expect("Failed to lock test contexts")
+            .push(context.clone());
+
+        Ok(context)
+    }
+
+    /// Creates a context that `report` does not see and that leaves the panic hook alone.
+    pub(crate) fn new(name: &str, artifacts_dir: PathBuf, expected_checks: usize) -> io::Result<Arc<Self>> {
+        Ok(Arc::new(Self {
+            name: name.to_string(),
+            expected_checks,
+            results: Mutex::new(Vec::new()),
+            artifacts: FailureArtifacts::new(artifacts_dir)?
+        }))
+    }
+
+    pub fn assert(&self, condition: bool, msg: impl Into<String>) {
+        self.results.lock()
+            .expect("Failed to lock test results")
+            .push(CheckResult {
+                passed: condition,
+                message: msg.into()
+            });
+    }
+
+    pub fn expect_eq<T: PartialEq + Debug>(&self, actual: T, expected: T, msg: impl Into<String>) {
+        let msg = msg.into();
+        if actual == expected {
+            self.assert(true, msg)
+        } else {
+            self.assert(false, format!("{}: expected {:?}, got {:?}", msg, expected, actual))
+        }
+    }
+
+    /// True once every expected check ran and, if one failed, its artifacts have been captured.
+    pub fn is_complete(&self) -> bool {
+        let checks_ran = self.results.lock()
+            .expect("Failed to lock test results")
+            .len() >= self.expected_checks;
+
+        checks_ran && (!self.failed() || self.artifacts.is_captured())
+    }
+
+    /// Records one frame of input for the failure artifacts.
+    pub fn record_input(&self, frame: String) {
+        self.artifacts.record_input(frame)
+    }
+
+    /// Saves failure artifacts the first time this is called after a check failed.
+    pub fn capture_if_failed(&self, ecs: &World, screenshot: bool) -> io::Result<()> {
+        if !self.failed() || self.artifacts.is_captured() {
+            return Ok(())
+        }
+
+        let failures: Vec<String> = self.results().into_iter()
+            .filter(|result| !result.passed)
+            .map(|result| result.message)
+            .collect();
+
+        self.artifacts.capture(ecs, &failures.join("\n"), screenshot)
+    }
+
+    pub fn results(&self) -> Vec<CheckResult> {
+        self.results.lock()
+            .expect("Failed to lock test results")
+            .clone()
+    }
+
+    /// Whether any check recorded so far has failed.
+    pub fn failed(&self) -> bool {
+        self.results.lock()
+            .expect("Failed to lock test results")
+            .iter()
+            .any(|result| !result.passed)
+    }
+
+    /// A context passes when every expected check ran and none of them failed.
+    pub fn passed(&self) -> bool {
+        let results = self.results();
+        results.len() >= self.expected_checks && results.iter().all(|result| result.passed)
+    }
+}
+
+/// Prints the results of every registered context and returns whether all of them passed.
+pub fn report() -> bool {
+    let contexts = CONTEXTS.lock()
+        .expect("Failed to lock test contexts");
+
+    let mut all_passed = true;
+    for context in contexts.iter() {
+        let results = context.results();
+
+        for result in results.iter().filter(|result| !result.passed) {
+            eprintln!("[{}] FAILED: {}", context.name, result.message);
+        }
+
+        if results.len() < context.expected_checks {
+            eprintln!("[{}] FAILED: only {} of {} checks ran", context.name, results.len(), context.expected_checks);
+        }
+
+        let passed = context.passed();
+        println!("[{}] {} ({} checks)", context.name, if passed { "PASSED" } else { "FAILED" }, results.len());
+        all_passed &= passed;
+    }
+
+    all_passed
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// A context for unit tests, with artifacts under the system temp directory.
+    pub(crate) fn test_context(name: &str, expected_checks: usize) -> Arc<TestContext> {
+        let artifacts_dir = std::env::temp_dir().join("test_harness").join(name);
+        TestContext::new(name, artifacts_dir, expected_checks).expect("Failed to create test context")
+    }
+
+    #[test]
+    fn incomplete_until_every_check_runs() {
+        let context = test_context("incomplete", 2);
+
+        context.assert(true, "first");
+        assert!(!context.is_complete());
+        assert!(!context.passed());
+
+        context.assert(true, "second");
+        assert!(context.is_complete());
+        assert!(context.passed());
+    }
+
+    #[test]
+    fn failure_waits_for_artifacts() {
+        let context = test_context("failure", 1);
+
+        context.expect_eq(1, 2, "mismatch");
+        assert!(context.failed());
+        assert!(!context.passed());
+        assert!(!context.is_complete());
+        assert_eq!(context.results()[0].message, "mismatch: expected 2, got 1");
+    }
+
+    #[test]
+    fn extra_checks_still_count() {
+        let context = test_context("extra", 1);
+
+        context.assert(true, "first");
+        context.assert(true, "second");
+        assert!(context.passed());
+    }
+}
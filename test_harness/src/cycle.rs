@@ -0,0 +1,227 @@
+use std::fmt::{Debug, Formatter};
+use std::marker::PhantomData;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering::Relaxed;
+use std::sync::{Arc, RwLock};
+use anyhow::Result;
+use specs::World;
+use game_engine::components::ComponentMux;
+use game_engine::input::multi_input::MultiInput;
+use game_engine::load::create_entity_vec;
+use game_engine::scenes::Scene;
+use game_engine::scenes::scene_stack::SceneTransition;
+use crate::soak::{SoakMonitor, SOAK_FRAME_WINDOW};
+
+/// Updates a pushed `SpawnScene` stays on top of the stack before popping itself.
+pub const SPAWN_SCENE_FRAMES: u64 = 10;
+
+/// A scene that loads `entity_paths` on its first update and pops itself `frames` updates later.
+///
+/// It never deletes what it loaded, the same as the test scenes, so anything still in the World
+/// after it pops was left behind by the engine. Only the top of the stack is drawn, so it ticks the
+/// soak monitor, if any, in place of the scene underneath.
+pub struct SpawnScene<M> {
+    entity_paths: Vec<String>,
+    frames: u64,
+    frame_count: AtomicU64,
+    soak: Option<Arc<SoakMonitor>>,
+    _mux: PhantomData<fn() -> M>
+}
+
+impl<M> SpawnScene<M> {
+    pub fn new(entity_paths: Vec<String>, frames: u64, soak: Option<Arc<SoakMonitor>>) -> Self {
+        Self {
+            entity_paths,
+            frames,
+            frame_count: AtomicU64::new(0),
+            soak,
+            _mux: PhantomData
+        }
+    }
+}
+
+impl<M> Debug for SpawnScene<M> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Spawn Scene")
+            .field("Entity Paths", &self.entity_paths)
+            .field("Frames", &self.frames)
+            .finish()
+    }
+}
+
+impl<M: ComponentMux + 'static> Scene<MultiInput> for SpawnScene<M> {
+    fn update(&self, ecs: Arc<RwLock<World>>) -> Result<SceneTransition<MultiInput>> {
+        let frame = self.frame_count.fetch_add(1, Relaxed);
+
+        if frame == 0 {
+            create_entity_vec::<M>(&self.entity_paths, ecs)?;
+        }
+
+        let soak_done = self.soak.as_ref()
+            .map_or(false, |soak| soak.is_done());
+
+        if frame + 1 >= self.frames || soak_done {
+            Ok(SceneTransition::POP)
+        } else {
+            Ok(SceneTransition::NONE)
+        }
+    }
+
+    fn draw(&self, ecs: Arc<RwLock<World>>) -> Result<()> {
+        if let Some(soak) = &self.soak {
+            soak.tick(&ecs.read().expect("Failed to acquire read lock for World"));
+        }
+
+        Ok(())
+    }
+
+    fn interact(&self, _ecs: Arc<RwLock<World>>, _input: &MultiInput) -> Result<()> {
+        Ok(())
+    }
+
+    fn get_name(&self) -> String {
+        String::from("Spawn Scene")
+    }
+
+    fn is_finished(&self, _ecs: Arc<RwLock<World>>) -> Result<bool> {
+        Ok(false)
+    }
+}
+
+/// Repeatedly pushes a `SpawnScene` over the scene that owns it.
+///
+/// Only the top of the stack is updated, so every update of the owning scene is a point where no
+/// `SpawnScene` is loaded: the start of the run, or just after one popped. Call `update` from
+/// every `Scene::update` of the owning scene and return the transition it gives back.
+pub struct SceneCycler<M> {
+    entity_paths: Vec<String>,
+    frames: u64,
+    max_cycles: Option<u64>,
+    soak: Option<Arc<SoakMonitor>>,
+    boundaries: AtomicU64,
+    _mux: PhantomData<fn() -> M>
+}
+
+impl<M: ComponentMux + 'static> SceneCycler<M> {
+    /// Cycles `max_cycles` times, or until the run ends when it is None, keeping each pushed scene
+    /// for `SPAWN_SCENE_FRAMES` updates.
+    pub fn new(entity_paths: Vec<String>, max_cycles: Option<u64>) -> Self {
+        Self {
+            entity_paths,
+            frames: SPAWN_SCENE_FRAMES,
+            max_cycles,
+            soak: None,
+            boundaries: AtomicU64::new(0),
+            _mux: PhantomData
+        }
+    }
+
+    /// Cycles until `soak` is done, keeping each pushed scene for about a minute so the entities it
+    /// leaves behind accumulate slowly enough to last a long soak.
+    pub fn soak(entity_paths: Vec<String>, soak: Arc<SoakMonitor>) -> Self {
+        Self {
+            frames: SOAK_FRAME_WINDOW as u64,
+            soak: Some(soak),
+            ..Self::new(entity_paths, None)
+        }
+    }
+
+    /// Calls `at_boundary` with the number of completed cycles, 0 before the first push, then pushes
+    /// the next `SpawnScene`. After the last cycle the owning scene stays on top.
+    pub fn update(&self, at_boundary: impl FnOnce(u64)) -> SceneTransition<MultiInput> {
+        if self.is_done() {
+            return SceneTransition::NONE
+        }
+
+        let completed = self.boundaries.fetch_add(1, Relaxed);
+        at_boundary(completed);
+
+        if self.is_done() {
+            SceneTransition::NONE
+        } else {
+            SceneTransition::PUSH(Box::new(SpawnScene::<M>::new(self.entity_paths.clone(), self.frames, self.soak.clone())))
+        }
+    }
+
+    /// Number of pushed scenes that have popped so far.
+    pub fn completed(&self) -> u64 {
+        self.boundaries.load(Relaxed).saturating_sub(1)
+    }
+
+    pub fn is_done(&self) -> bool {
+        let cycles_done = self.max_cycles
+            .map_or(false, |max_cycles| self.boundaries.load(Relaxed) > max_cycles);
+        let soak_done = self.soak.as_ref()
+            .map_or(false, |soak| soak.is_done());
+
+        cycles_done || soak_done
+    }
+}
+
+impl<M> Debug for SceneCycler<M> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Scene Cycler")
+            .field("Entity Paths", &self.entity_paths)
+            .field("Frames", &self.frames)
+            .field("Max Cycles", &self.max_cycles)
+            .field("Boundaries", &self.boundaries.load(Relaxed))
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Error;
+    use game_engine::components::ComponentLoader;
+    use game_engine::load::JSONLoad;
+
+    struct NoMux;
+
+    impl ComponentMux for NoMux {
+        fn map_json_to_loader(_json: JSONLoad) -> Result<Box<dyn ComponentLoader>> {
+            Err(Error::msg("No components"))
+        }
+    }
+
+    fn is_push(transition: &SceneTransition<MultiInput>) -> bool {
+        matches!(transition, SceneTransition::PUSH(_))
+    }
+
+    #[test]
+    fn reports_every_boundary_then_stops() {
+        let cycler = SceneCycler::<NoMux>::new(Vec::new(), Some(2));
+        let mut boundaries = Vec::new();
+
+        for _ in 0..2 {
+            assert!(is_push(&cycler.update(|completed| boundaries.push(completed))));
+        }
+        assert!(!is_push(&cycler.update(|completed| boundaries.push(completed))));
+        assert!(!is_push(&cycler.update(|completed| boundaries.push(completed))));
+
+        assert_eq!(boundaries, vec![0, 1, 2]);
+        assert_eq!(cycler.completed(), 2);
+        assert!(cycler.is_done());
+    }
+
+    #[test]
+    fn unbounded_cycler_keeps_pushing() {
+        let cycler = SceneCycler::<NoMux>::new(Vec::new(), None);
+
+        for _ in 0..100 {
+            assert!(is_push(&cycler.update(|_| {})));
+        }
+        assert!(!cycler.is_done());
+    }
+
+    #[test]
+    fn spawn_scene_pops_after_its_frames() {
+        let scene = SpawnScene::<NoMux>::new(Vec::new(), 3, None);
+        let ecs = Arc::new(RwLock::new(World::new()));
+
+        for _ in 0..2 {
+            assert!(matches!(scene.update(ecs.clone()).unwrap(), SceneTransition::NONE));
+        }
+        assert!(matches!(scene.update(ecs).unwrap(), SceneTransition::POP));
+    }
+}
@@ -0,0 +1,105 @@
+use std::sync::Mutex;
+use specs::{World, WorldExt, ReadStorage, Join};
+use game_engine::graphics::texture::TextureHandle;
+use game_engine::graphics::transform::Transform;
+use crate::context::TestContext;
+
+/// What can be counted from the World. The engine doesn't expose texture or GPU memory, so texture
+/// handles stand in for uploaded textures.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ResourceCounts {
+    pub entities: usize,
+    pub transforms: usize,
+    pub texture_handles: usize
+}
+
+impl ResourceCounts {
+    /// `ecs` must have Transform and TextureHandle registered.
+    pub fn of(ecs: &World) -> Self {
+        let (transforms, texture_handles): (ReadStorage<Transform>, ReadStorage<TextureHandle>) = ecs.system_data();
+
+        Self {
+            entities: ecs.entities().join().count(),
+            transforms: transforms.join().count(),
+            texture_handles: texture_handles.join().count()
+        }
+    }
+}
+
+/// Checks that resource counts return to what they were before the first push/pop cycle.
+/// Records one check per completed cycle, so a run of `n` cycles expects `n` checks.
+#[derive(Debug, Default)]
+pub struct LeakCheck {
+    baseline: Mutex<Option<ResourceCounts>>
+}
+
+impl LeakCheck {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Pass the counts at every boundary reported by `SceneCycler::update`. Cycle 0 is the baseline.
+    pub fn record(&self, cycle: u64, counts: ResourceCounts, test_context: &TestContext) {
+        tracing::info!(
+            cycle,
+            entities = counts.entities,
+            transforms = counts.transforms,
+            texture_handles = counts.texture_handles,
+            "leak check"
+        );
+
+        let mut baseline = self.baseline.lock()
+            .expect("Failed to lock leak check baseline");
+
+        match *baseline {
+            None => *baseline = Some(counts),
+            Some(baseline) => test_context.expect_eq(
+                counts,
+                baseline,
+                format!("Resource counts return to baseline after push/pop cycle {}", cycle)
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::tests::test_context;
+
+    fn counts(entities: usize) -> ResourceCounts {
+        ResourceCounts { entities, transforms: entities, texture_handles: entities }
+    }
+
+    #[test]
+    fn first_cycle_is_the_baseline() {
+        let context = test_context("leak_baseline", 1);
+        let leak_check = LeakCheck::new();
+
+        leak_check.record(0, counts(3), &context);
+        assert!(context.results().is_empty());
+
+        leak_check.record(1, counts(3), &context);
+        assert!(context.passed());
+    }
+
+    #[test]
+    fn growth_after_a_cycle_fails() {
+        let context = test_context("leak_growth", 1);
+        let leak_check = LeakCheck::new();
+
+        leak_check.record(0, counts(3), &context);
+        leak_check.record(1, counts(4), &context);
+        assert!(context.failed());
+    }
+
+    #[test]
+    fn counts_every_registered_component() {
+        let mut ecs = World::new();
+        ecs.register::<Transform>();
+        ecs.register::<TextureHandle>();
+        ecs.create_entity().build();
+
+        assert_eq!(ResourceCounts::of(&ecs), ResourceCounts { entities: 1, transforms: 0, texture_handles: 0 });
+    }
+}
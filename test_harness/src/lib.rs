@@ -0,0 +1,12 @@
+pub mod artifacts;
+pub mod baseline;
+pub mod cli;
+pub mod context;
+pub mod cycle;
+pub mod leak;
+pub mod paths;
+pub mod projection;
+pub mod recording;
+pub mod rng;
+pub mod soak;
+pub mod stats;
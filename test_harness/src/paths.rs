@@ -0,0 +1,33 @@
+use std::io;
+use std::path::{Path, PathBuf};
+use game_engine::load::LOAD_PATH;
+use crate::artifacts::ARTIFACTS_DIR;
+
+/// The workspace root. Asset paths inside the JSON files are written relative to it.
+pub fn workspace_root() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .expect("test_harness is not inside a workspace")
+        .to_path_buf()
+}
+
+/// Changes to the workspace root unless the assets can already be found from the current directory,
+/// so the tests work the same when run through `cargo run -p` or from inside a crate directory.
+pub fn enter_workspace_root() -> io::Result<()> {
+    if Path::new(LOAD_PATH).is_dir() {
+        return Ok(())
+    }
+
+    std::env::set_current_dir(workspace_root())
+}
+
+/// Directory a scenario's failure artifacts and test runner output go in: `artifacts/<crate>/<scene>/`.
+pub fn artifacts_dir(crate_name: &str, scene: &str) -> PathBuf {
+    workspace_root().join(ARTIFACTS_DIR).join(crate_name).join(scene)
+}
+
+/// Directory a test binary writes its log file into: `artifacts/<crate>/`, which is ignored by git
+/// and collected by the test runner along with its reports.
+pub fn log_dir(crate_name: &str) -> PathBuf {
+    workspace_root().join(ARTIFACTS_DIR).join(crate_name)
+}
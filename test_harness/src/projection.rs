@@ -0,0 +1,36 @@
+use glam::{Mat4, Vec2};
+
+/// The projection the test scenes render with: one world unit per pixel, with the origin at the
+/// bottom left of a `width` by `height` window.
+pub fn screen_projection(width: f32, height: f32) -> Mat4 {
+    Mat4::orthographic_rh_gl(0.0, width, 0.0, height, -1.0, 10.0)
+}
+
+/// World position drawn at `screen`, a window position in pixels from the top left, as reported by
+/// GLFW for cursor input.
+pub fn screen_to_world(projection: &Mat4, viewport: Vec2, screen: Vec2) -> Vec2 {
+    let ndc = Vec2::new(2.0 * screen.x / viewport.x - 1.0, 1.0 - 2.0 * screen.y / viewport.y);
+
+    projection.inverse().project_point3(ndc.extend(0.0)).truncate()
+}
+
+/// Window position in pixels from the top left that `world` is drawn at.
+pub fn world_to_screen(projection: &Mat4, viewport: Vec2, world: Vec2) -> Vec2 {
+    let ndc = projection.project_point3(world.extend(0.0));
+
+    Vec2::new((ndc.x + 1.0) / 2.0 * viewport.x, (1.0 - ndc.y) / 2.0 * viewport.y)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn corners_map_to_window_corners() {
+        let viewport = Vec2::new(960.0, 540.0);
+        let projection = screen_projection(viewport.x, viewport.y);
+
+        assert!(world_to_screen(&projection, viewport, Vec2::ZERO).abs_diff_eq(Vec2::new(0.0, 540.0), 1e-3));
+        assert!(world_to_screen(&projection, viewport, viewport).abs_diff_eq(Vec2::new(960.0, 0.0), 1e-3));
+    }
+}
@@ -0,0 +1,46 @@
+use std::fs::{create_dir_all, File};
+use std::io::{self, LineWriter, Write};
+use std::path::Path;
+use std::sync::Mutex;
+
+/// Writes every frame's input to the file given with `--record`, one `artifacts::describe_input`
+/// line per frame. Lines are flushed as they are written, so a crash keeps everything before it.
+#[derive(Debug)]
+pub struct InputRecording {
+    writer: Mutex<LineWriter<File>>
+}
+
+impl InputRecording {
+    pub fn create(path: &Path) -> io::Result<Self> {
+        if let Some(dir) = path.parent() {
+            create_dir_all(dir)?;
+        }
+
+        Ok(Self {
+            writer: Mutex::new(LineWriter::new(File::create(path)?))
+        })
+    }
+
+    pub fn record(&self, frame: &str) -> io::Result<()> {
+        writeln!(self.writer.lock().expect("Failed to lock input recording"), "{}", frame)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::read_to_string;
+    use tempfile::tempdir;
+
+    #[test]
+    fn writes_one_line_per_frame() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("recordings").join("input.txt");
+
+        let recording = InputRecording::create(&path).unwrap();
+        recording.record("frame 0: pressed [] held []").unwrap();
+        recording.record("frame 1: pressed [Left] held []").unwrap();
+
+        assert_eq!(read_to_string(&path).unwrap(), "frame 0: pressed [] held []\nframe 1: pressed [Left] held []\n");
+    }
+}
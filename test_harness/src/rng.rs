@@ -0,0 +1,83 @@
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+use std::sync::Mutex;
+use crate::cli::TestArgs;
+
+/// Seed used by `--perf-gate` runs without `--seed`, so gated runs always measure the same scene.
+pub const PERF_GATE_SEED: u64 = 0;
+
+/// Seedable RNG inserted into the World as a resource, so a randomized scene can be reproduced by
+/// passing the printed seed back with `--seed`.
+#[derive(Debug)]
+pub struct SharedRng {
+    seed: u64,
+    rng: Mutex<StdRng>
+}
+
+impl SharedRng {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            rng: Mutex::new(StdRng::seed_from_u64(seed))
+        }
+    }
+
+    /// Seeded from `--seed`, or `PERF_GATE_SEED` under `--perf-gate`, or randomly.
+    pub fn from_args(args: &TestArgs) -> Self {
+        Self::new(args.seed.unwrap_or_else(|| if args.perf_gate { PERF_GATE_SEED } else { rand::random() }))
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Runs `f` with the shared stream. Calls happen in whatever order the scene makes them, so results
+    /// are only reproducible if that order is deterministic; use `fork` for independent streams.
+    pub fn with<T>(&self, f: impl FnOnce(&mut StdRng) -> T) -> T {
+        f(&mut self.rng.lock().expect("Failed to lock RNG"))
+    }
+
+    /// Creates an RNG seeded from the shared one, for a system that should not affect other users' sequences.
+    pub fn fork(&self) -> StdRng {
+        self.with(|rng| StdRng::seed_from_u64(rng.gen()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    fn stream(rng: &mut StdRng) -> Vec<u64> {
+        (0..8).map(|_| rng.gen()).collect()
+    }
+
+    #[test]
+    fn same_seed_forks_same_streams() {
+        let (first, second) = (SharedRng::new(42), SharedRng::new(42));
+
+        for _ in 0..3 {
+            assert_eq!(stream(&mut first.fork()), stream(&mut second.fork()));
+        }
+    }
+
+    #[test]
+    fn perf_gate_uses_fixed_seed() {
+        let args = TestArgs::try_parse_from(&["test", "--frames", "10", "--perf-gate"]).unwrap();
+        assert_eq!(SharedRng::from_args(&args).seed(), PERF_GATE_SEED);
+
+        let args = TestArgs::try_parse_from(&["test", "--frames", "10", "--perf-gate", "--seed", "7"]).unwrap();
+        assert_eq!(SharedRng::from_args(&args).seed(), 7);
+    }
+
+    #[test]
+    fn forks_are_independent() {
+        let rng = SharedRng::new(42);
+        assert_ne!(stream(&mut rng.fork()), stream(&mut rng.fork()));
+    }
+
+    #[test]
+    fn different_seeds_fork_different_streams() {
+        assert_ne!(stream(&mut SharedRng::new(1).fork()), stream(&mut SharedRng::new(2).fork()));
+    }
+}
@@ -0,0 +1,206 @@
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use specs::{World, WorldExt, Join};
+use sysinfo::{get_current_pid, Pid, ProcessExt, System, SystemExt};
+use crate::context::TestContext;
+
+pub const SAMPLE_INTERVAL: Duration = Duration::from_secs(60);
+/// Fewest samples `check` accepts. Each quarter of the run needs a few samples for the growth
+/// check to mean anything, so shorter soaks are rejected on the command line.
+pub const MIN_SAMPLES: usize = 10;
+/// How far memory may rise above the first quarter's peak before it counts as growth, so allocator
+/// noise on a flat run doesn't fail it.
+pub const MEMORY_TOLERANCE: f64 = 0.05;
+/// Number of checks `SoakMonitor::check` records.
+pub const SOAK_CHECKS: usize = 3;
+/// Frames kept by a soak run's `FrameStats`, about a minute at 60 fps.
+pub const SOAK_FRAME_WINDOW: usize = 60 * 60;
+
+/// Shortest soak, in hours, that collects `MIN_SAMPLES` samples. Samples drift later by up to a
+/// frame each, so one extra interval is allowed for.
+pub fn min_soak_hours() -> f64 {
+    ((MIN_SAMPLES as u64 + 1) * SAMPLE_INTERVAL.as_secs()) as f64 / (60.0 * 60.0)
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct SoakSample {
+    pub elapsed: Duration,
+    pub memory_kb: u64,
+    pub fps: f64,
+    pub entities: usize
+}
+
+#[derive(Debug)]
+struct SampleWindow {
+    start: Instant,
+    frames: u64
+}
+
+/// Samples process memory, frame rate, and entity count once a minute for the length of a soak run.
+/// Call `tick` once per frame from `Scene::draw` and `check` once `is_done` returns true.
+#[derive(Debug)]
+pub struct SoakMonitor {
+    start: Instant,
+    duration: Duration,
+    pid: Pid,
+    system: Mutex<System>,
+    window: Mutex<SampleWindow>,
+    samples: Mutex<Vec<SoakSample>>
+}
+
+impl SoakMonitor {
+    pub fn new(hours: f64) -> Self {
+        let start = Instant::now();
+        let duration = Duration::from_secs_f64(hours * 60.0 * 60.0);
+        let sample_count = (duration.as_secs() / SAMPLE_INTERVAL.as_secs()) as usize + 1;
+
+        Self {
+            start,
+            duration,
+            pid: get_current_pid().expect("Failed to get the current process id"),
+            system: Mutex::new(System::new()),
+            window: Mutex::new(SampleWindow { start, frames: 0 }),
+            samples: Mutex::new(Vec::with_capacity(sample_count))
+        }
+    }
+
+    pub fn tick(&self, ecs: &World) {
+        let mut window = self.window.lock()
+            .expect("Failed to lock soak sample window");
+        window.frames += 1;
+
+        let window_length = window.start.elapsed();
+        if window_length < SAMPLE_INTERVAL {
+            return
+        }
+
+        let mut system = self.system.lock()
+            .expect("Failed to lock system info");
+        system.refresh_process(self.pid);
+
+        let sample = SoakSample {
+            elapsed: self.start.elapsed(),
+            memory_kb: system.process(self.pid).map_or(0, |process| process.memory()),
+            fps: window.frames as f64 / window_length.as_secs_f64(),
+            entities: ecs.entities().join().count()
+        };
+
+        tracing::info!(
+            elapsed_secs = sample.elapsed.as_secs(),
+            memory_kb = sample.memory_kb,
+            fps = sample.fps,
+            entities = sample.entities,
+            "soak sample"
+        );
+        println!(
+            "[soak] {}s: {} KB, {:.1} fps, {} entities",
+            sample.elapsed.as_secs(), sample.memory_kb, sample.fps, sample.entities
+        );
+
+        self.samples.lock()
+            .expect("Failed to lock soak samples")
+            .push(sample);
+
+        *window = SampleWindow { start: Instant::now(), frames: 0 };
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.start.elapsed() >= self.duration
+    }
+
+    pub fn samples(&self) -> Vec<SoakSample> {
+        self.samples.lock()
+            .expect("Failed to lock soak samples")
+            .clone()
+    }
+
+    /// Records `SOAK_CHECKS` checks: one that enough samples were taken, one for memory growth, and
+    /// one for entity count growth.
+    pub fn check(&self, test_context: &TestContext) {
+        let samples = self.samples();
+
+        test_context.assert(
+            samples.len() >= MIN_SAMPLES,
+            format!("Soak took {} samples, growth checks need at least {}", samples.len(), MIN_SAMPLES)
+        );
+
+        let memory: Vec<u64> = samples.iter().map(|sample| sample.memory_kb).collect();
+        test_context.assert(
+            !grows(&memory, MEMORY_TOLERANCE),
+            format!("Memory grew over the run: {:?}", memory)
+        );
+
+        let entities: Vec<u64> = samples.iter().map(|sample| sample.entities as u64).collect();
+        test_context.assert(
+            !grows(&entities, 0.0),
+            format!("Entity count grew over the run: {:?}", entities)
+        );
+    }
+}
+
+/// True when every value in the last quarter of the run is above the highest value in the first
+/// quarter by more than `tolerance`. Comparing whole quarters means a flat stretch or a single
+/// dip doesn't hide a leak, and a single spike doesn't fake one.
+fn grows(values: &[u64], tolerance: f64) -> bool {
+    if values.len() < MIN_SAMPLES {
+        return false
+    }
+
+    let quarter = values.len() / 4;
+    let first_peak = values[..quarter].iter().max().copied().unwrap_or_default();
+    let last_low = values[values.len() - quarter..].iter().min().copied().unwrap_or_default();
+
+    last_low as f64 > first_peak as f64 * (1.0 + tolerance)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn short_runs_never_grow() {
+        let values: Vec<u64> = (0..MIN_SAMPLES as u64 - 1).collect();
+        assert!(!grows(&values, 0.0));
+    }
+
+    #[test]
+    fn steady_growth_grows() {
+        let values: Vec<u64> = (0..MIN_SAMPLES as u64 * 3).collect();
+        assert!(grows(&values, 0.0));
+    }
+
+    #[test]
+    fn flat_sample_does_not_hide_growth() {
+        let mut values: Vec<u64> = (0..MIN_SAMPLES as u64 * 3).collect();
+        let last = values.len() - 1;
+        values[last] = values[last - 1];
+        assert!(grows(&values, 0.0));
+    }
+
+    #[test]
+    fn stepwise_growth_grows() {
+        let mut values = vec![100; MIN_SAMPLES * 2];
+        values.extend(vec![200; MIN_SAMPLES]);
+        assert!(grows(&values, 0.0));
+    }
+
+    #[test]
+    fn flat_run_does_not_grow() {
+        assert!(!grows(&vec![100; MIN_SAMPLES * 3], 0.0));
+    }
+
+    #[test]
+    fn single_spike_does_not_grow() {
+        let mut values = vec![100; MIN_SAMPLES * 3];
+        *values.last_mut().unwrap() = 1000;
+        assert!(!grows(&values, 0.0));
+    }
+
+    #[test]
+    fn growth_within_tolerance_does_not_grow() {
+        let mut values = vec![100; MIN_SAMPLES * 2];
+        values.extend(vec![104; MIN_SAMPLES * 2]);
+        assert!(!grows(&values, MEMORY_TOLERANCE));
+        assert!(grows(&values, 0.0));
+    }
+}
@@ -0,0 +1,192 @@
+use std::collections::VecDeque;
+use std::fmt::{Display, Formatter};
+use std::sync::Mutex;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering::Relaxed;
+use std::time::{Duration, Instant};
+
+/// Records the time between consecutive frames, and how much of each frame was spent waiting on
+/// locks acquired through `timed`. Call `tick` once per frame from `Scene::draw`.
+#[derive(Debug, Default)]
+pub struct FrameStats {
+    last_frame: Mutex<Option<Instant>>,
+    frames: AtomicU64,
+    capacity: Option<usize>,
+    frame_times: Mutex<VecDeque<Duration>>,
+    current_lock_wait_nanos: AtomicU64,
+    lock_waits: Mutex<VecDeque<Duration>>
+}
+
+impl FrameStats {
+    /// Keeps every frame for the summary. Use `bounded` for runs with no fixed length.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Keeps only the last `capacity` frames, allocated up front so memory use stays flat.
+    pub fn bounded(capacity: usize) -> Self {
+        Self {
+            capacity: Some(capacity),
+            frame_times: Mutex::new(VecDeque::with_capacity(capacity)),
+            lock_waits: Mutex::new(VecDeque::with_capacity(capacity)),
+            ..Self::default()
+        }
+    }
+
+    fn push(&self, times: &Mutex<VecDeque<Duration>>, time: Duration) {
+        let mut times = times.lock()
+            .expect("Failed to lock frame times");
+
+        if self.capacity == Some(times.len()) {
+            times.pop_front();
+        }
+        times.push_back(time);
+    }
+
+    pub fn tick(&self) {
+        let now = Instant::now();
+        let mut last_frame = self.last_frame.lock()
+            .expect("Failed to lock last frame time");
+
+        // Waits before the first frame happened during loading, so they are dropped along with it
+        let lock_wait = Duration::from_nanos(self.current_lock_wait_nanos.swap(0, Relaxed));
+        if let Some(last_frame) = *last_frame {
+            self.push(&self.frame_times, now - last_frame);
+            self.push(&self.lock_waits, lock_wait);
+        }
+
+        *last_frame = Some(now);
+        self.frames.fetch_add(1, Relaxed);
+    }
+
+    /// Runs `acquire` and adds the time it took to the current frame's lock wait.
+    pub fn timed<T>(&self, acquire: impl FnOnce() -> T) -> T {
+        let start = Instant::now();
+        let guard = acquire();
+        self.current_lock_wait_nanos.fetch_add(start.elapsed().as_nanos() as u64, Relaxed);
+
+        guard
+    }
+
+    /// Number of frames ticked so far, including any no longer kept by a bounded `FrameStats`.
+    pub fn frames(&self) -> usize {
+        self.frames.load(Relaxed) as usize
+    }
+
+    /// Summary of the frames currently kept.
+    pub fn summary(&self) -> FrameSummary {
+        let mut frame_times: Vec<Duration> = self.frame_times.lock()
+            .expect("Failed to lock frame times")
+            .iter()
+            .copied()
+            .collect();
+        frame_times.sort();
+
+        let mut lock_waits: Vec<Duration> = self.lock_waits.lock()
+            .expect("Failed to lock lock wait times")
+            .iter()
+            .copied()
+            .collect();
+        lock_waits.sort();
+
+        FrameSummary {
+            frames: frame_times.len(),
+            mean: mean(&frame_times),
+            p50: percentile(&frame_times, 0.50),
+            p95: percentile(&frame_times, 0.95),
+            max: frame_times.last().copied().unwrap_or_default(),
+            lock_wait_mean: mean(&lock_waits),
+            lock_wait_p95: percentile(&lock_waits, 0.95)
+        }
+    }
+}
+
+fn mean(durations: &[Duration]) -> Duration {
+    if durations.is_empty() {
+        return Duration::default()
+    }
+
+    durations.iter().sum::<Duration>() / durations.len() as u32
+}
+
+/// `durations` must be sorted.
+fn percentile(durations: &[Duration], p: f64) -> Duration {
+    durations
+        .get(((durations.len() as f64 * p).ceil() as usize).saturating_sub(1))
+        .copied()
+        .unwrap_or_default()
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct FrameSummary {
+    pub frames: usize,
+    pub mean: Duration,
+    pub p50: Duration,
+    pub p95: Duration,
+    pub max: Duration,
+    pub lock_wait_mean: Duration,
+    pub lock_wait_p95: Duration
+}
+
+impl FrameSummary {
+    pub fn fps(&self) -> f64 {
+        if self.mean.as_secs_f64() > 0.0 { 1.0 / self.mean.as_secs_f64() } else { 0.0 }
+    }
+}
+
+impl Display for FrameSummary {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} frames, {:.1} fps, mean {:?}, p50 {:?}, p95 {:?}, max {:?}, lock wait mean {:?}, p95 {:?}",
+            self.frames, self.fps(), self.mean, self.p50, self.p95, self.max, self.lock_wait_mean, self.lock_wait_p95
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn millis(values: &[u64]) -> Vec<Duration> {
+        values.iter().copied().map(Duration::from_millis).collect()
+    }
+
+    #[test]
+    fn percentile_of_empty_is_zero() {
+        assert_eq!(percentile(&[], 0.95), Duration::default());
+    }
+
+    #[test]
+    fn percentile_picks_nearest_rank() {
+        let durations = millis(&[1, 2, 3, 4, 5, 6, 7, 8, 9, 10]);
+
+        assert_eq!(percentile(&durations, 0.0), Duration::from_millis(1));
+        assert_eq!(percentile(&durations, 0.5), Duration::from_millis(5));
+        assert_eq!(percentile(&durations, 0.95), Duration::from_millis(10));
+        assert_eq!(percentile(&durations, 1.0), Duration::from_millis(10));
+    }
+
+    #[test]
+    fn bounded_keeps_last_frames() {
+        let stats = FrameStats::bounded(2);
+        for _ in 0..5 {
+            stats.tick();
+        }
+
+        assert_eq!(stats.frames(), 5);
+        assert_eq!(stats.summary().frames, 2);
+    }
+
+    #[test]
+    fn first_frame_drops_loading_lock_waits() {
+        let stats = FrameStats::new();
+        stats.timed(|| std::thread::sleep(Duration::from_millis(20)));
+        stats.tick();
+        stats.tick();
+
+        let summary = stats.summary();
+        assert_eq!(summary.frames, 1);
+        assert!(summary.lock_wait_p95 < Duration::from_millis(20));
+    }
+}
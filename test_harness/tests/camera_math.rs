@@ -0,0 +1,169 @@
+use game_engine::camera::Camera;
+use game_engine::camera::orthographic_camera::{OrthographicCameraLoader, ORTHOGRAPHIC_CAMERA_LOAD_ID};
+use game_engine::components::{ComponentMux, ComponentLoader};
+use game_engine::graphics::transform::{Transform, TransformLoader, TRANSFORM_LOAD_ID};
+use game_engine::load::{JSONLoad, create_entity_vec};
+use anyhow::{Result, Error};
+use glam::{Vec2, Vec3};
+use proptest::prelude::*;
+use serde_json::json;
+use specs::{World, WorldExt, ReadStorage, Join};
+use std::fs::write;
+use std::sync::atomic::Ordering::Relaxed;
+use std::sync::{Arc, RwLock};
+use tempfile::tempdir;
+use test_harness::projection::{screen_projection, screen_to_world, world_to_screen};
+
+const ENTITY_LOAD_ID: &str = "entity_loader";
+/// Pixels; the projection is exact to well under this for any window up to 8K.
+const EPSILON: f32 = 1e-2;
+
+struct TransformMux;
+
+impl ComponentMux for TransformMux {
+    fn map_json_to_loader(json: JSONLoad) -> Result<Box<dyn ComponentLoader>> {
+        match json.load_type_id.as_str() {
+            TRANSFORM_LOAD_ID => Ok(Box::new(TransformLoader::from_json(json)?)),
+            _ => Err(Error::msg("Invalid json load ID"))
+        }
+    }
+}
+
+fn new_world() -> Arc<RwLock<World>> {
+    let mut ecs = World::new();
+    ecs.register::<Transform>();
+
+    Arc::new(RwLock::new(ecs))
+}
+
+fn viewport() -> impl Strategy<Value = Vec2> {
+    (1u32..7680, 1u32..4320).prop_map(|(width, height)| Vec2::new(width as f32, height as f32))
+}
+
+fn vec3() -> impl Strategy<Value = Vec3> {
+    (-1.0e4f32..1.0e4, -1.0e4f32..1.0e4, -1.0e4f32..1.0e4).prop_map(|(x, y, z)| Vec3::new(x, y, z))
+}
+
+fn load_camera(position: Vec3, target: Vec3) -> Box<dyn Camera> {
+    let dir = tempdir().expect("Failed to create temp dir");
+    let camera_path = dir.path().join("orthographic_camera.json");
+    write(&camera_path, json!({
+        "load_type_id": ORTHOGRAPHIC_CAMERA_LOAD_ID,
+        "actual_value": {
+            "position": [position.x, position.y, position.z],
+            "target": [target.x, target.y, target.z]
+        }
+    }).to_string()).expect("Failed to write camera file");
+
+    let camera = OrthographicCameraLoader::new(camera_path.to_string_lossy().into_owned())
+        .load()
+        .run(new_world())
+        .expect("Failed to load camera");
+
+    Box::new(camera)
+}
+
+/// Creates one entity from a transform with `translation` and `rotation` and reads them back.
+fn transform_round_trip(translation: [f32; 2], rotation: f32) -> ([f32; 2], f32) {
+    let dir = tempdir().expect("Failed to create temp dir");
+    let transform_path = dir.path().join("transform.json");
+    let entity_path = dir.path().join("entity.json");
+
+    write(&transform_path, json!({
+        "load_type_id": TRANSFORM_LOAD_ID,
+        "actual_value": {
+            "translation": translation,
+            "scale": [1.0, 1.0],
+            "rotation": rotation
+        }
+    }).to_string()).expect("Failed to write transform file");
+    write(&entity_path, json!({
+        "load_type_id": ENTITY_LOAD_ID,
+        "actual_value": { "component_paths": [transform_path.to_string_lossy()] }
+    }).to_string()).expect("Failed to write entity file");
+
+    let ecs = new_world();
+    create_entity_vec::<TransformMux>(&vec![entity_path.to_string_lossy().into_owned()], ecs.clone())
+        .expect("Failed to create entity");
+
+    let ecs = ecs.read().expect("Failed to acquire read lock for World");
+    let transforms: ReadStorage<Transform> = ecs.system_data();
+    let transform = (&transforms).join().next().expect("Entity has no Transform");
+
+    (
+        [transform.translation[0].load(Relaxed), transform.translation[1].load(Relaxed)],
+        transform.rotation.load(Relaxed)
+    )
+}
+
+// The orthographic camera has no zoom and Transform has no parent, so projection under zoom and
+// transform hierarchy composition aren't covered until the engine has them.
+proptest! {
+    #[test]
+    fn screen_to_world_round_trips(viewport in viewport(), x in 0.0f32..1.0, y in 0.0f32..1.0) {
+        let projection = screen_projection(viewport.x, viewport.y);
+        let screen = Vec2::new(x, y) * viewport;
+
+        let round_trip = world_to_screen(&projection, viewport, screen_to_world(&projection, viewport, screen));
+        prop_assert!(round_trip.abs_diff_eq(screen, EPSILON), "{} became {}", screen, round_trip);
+    }
+
+    #[test]
+    fn world_to_screen_round_trips(viewport in viewport(), x in 0.0f32..1.0, y in 0.0f32..1.0) {
+        let projection = screen_projection(viewport.x, viewport.y);
+        let world = Vec2::new(x, y) * viewport;
+
+        let round_trip = screen_to_world(&projection, viewport, world_to_screen(&projection, viewport, world));
+        prop_assert!(round_trip.abs_diff_eq(world, EPSILON), "{} became {}", world, round_trip);
+    }
+
+    #[test]
+    fn world_units_are_pixels(viewport in viewport(), x in 0.0f32..1.0, y in 0.0f32..1.0, dx in -100.0f32..100.0) {
+        let projection = screen_projection(viewport.x, viewport.y);
+        let world = Vec2::new(x, y) * viewport;
+
+        let moved = world_to_screen(&projection, viewport, world + Vec2::new(dx, dx))
+            - world_to_screen(&projection, viewport, world);
+        prop_assert!(moved.abs_diff_eq(Vec2::new(dx, -dx), EPSILON), "moved {} for {}", moved, dx);
+    }
+
+    #[test]
+    fn camera_loads_position_and_target(position in vec3(), target in vec3()) {
+        let camera = load_camera(position, target);
+
+        prop_assert_eq!(camera.position(), position);
+        prop_assert_eq!(camera.target(), target);
+    }
+
+    #[test]
+    fn camera_position_and_target_are_independent(position in vec3(), target in vec3(), moved in vec3()) {
+        let mut camera = load_camera(position, target);
+
+        camera.set_position(moved);
+        prop_assert_eq!(camera.position(), moved);
+        prop_assert_eq!(camera.target(), target);
+
+        camera.set_target(moved);
+        prop_assert_eq!(camera.position(), moved);
+        prop_assert_eq!(camera.target(), moved);
+    }
+
+    #[test]
+    fn transform_loads_translation_and_rotation(
+        x in -1.0e6f32..1.0e6,
+        y in -1.0e6f32..1.0e6,
+        rotation in 0.0f32..std::f32::consts::TAU
+    ) {
+        prop_assert_eq!(transform_round_trip([x, y], rotation), ([x, y], rotation));
+    }
+
+    // camera_test expects `start + frames` after adding 1.0 once per frame, which only holds exactly
+    // for whole-number starting translations
+    #[test]
+    fn whole_translations_advance_exactly(start in -1.0e6f32..1.0e6, frames in 0u32..10_000) {
+        let start = start.round();
+        let advanced = (0..frames).fold(start, |translation, _| translation + 1.0);
+
+        prop_assert_eq!(advanced, start + frames as f32);
+    }
+}
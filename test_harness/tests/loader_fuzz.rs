@@ -0,0 +1,232 @@
+use game_engine::load::{JSONLoad, create_entity_vec};
+use game_engine::components::{ComponentMux, ComponentLoader};
+use game_engine::graphics::texture::{TextureLoader, TEXTURE_LOAD_ID};
+use game_engine::graphics::transform::{Transform, TransformLoader, TRANSFORM_LOAD_ID};
+use game_engine::input::multi_input::MultiInput;
+use game_engine::scenes::SceneLoader;
+use game_engine::scenes::scene_stack::SceneStackLoader;
+use anyhow::{Result, Error};
+use proptest::prelude::*;
+use serde_json::{json, Value};
+use specs::{World, WorldExt, ReadStorage, Join};
+use std::fs::write;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::path::Path;
+use std::sync::atomic::Ordering::Relaxed;
+use std::sync::{Arc, RwLock};
+use tempfile::{tempdir, TempDir};
+use test_harness::paths::{workspace_root, enter_workspace_root};
+
+const ENTITY_LOAD_ID: &str = "entity_loader";
+const SCENE_STACK_LOAD_ID: &str = "scene_stack";
+const TRANSFORM_ENTITY_FIXTURE: &str = "assets/JSON/bench/entities/bench_transform_entity.json";
+
+/// Transforms only, so entities can be created without a GL context.
+struct FuzzMux;
+
+impl ComponentMux for FuzzMux {
+    fn map_json_to_loader(json: JSONLoad) -> Result<Box<dyn ComponentLoader>> {
+        match json.load_type_id.as_str() {
+            TRANSFORM_LOAD_ID => Ok(Box::new(TransformLoader::from_json(json)?)),
+            _ => Err(Error::msg("Invalid json load ID"))
+        }
+    }
+}
+
+/// Scene loading needs a GL context, so every scene is rejected. That still exercises everything
+/// SceneStackLoader does before and around handing scene JSON to the factory.
+fn reject_scenes(json: JSONLoad) -> Result<Box<dyn SceneLoader<MultiInput>>> {
+    Err(Error::msg(format!("No fuzz scene for {}", json.load_type_id)))
+}
+
+fn arb_json() -> impl Strategy<Value = Value> {
+    let leaf = prop_oneof![
+        Just(Value::Null),
+        any::<bool>().prop_map(Value::Bool),
+        any::<i64>().prop_map(|i| json!(i)),
+        any::<f64>().prop_map(|f| json!(f)),
+        ".{0,16}".prop_map(Value::String),
+    ];
+
+    leaf.prop_recursive(4, 32, 8, |inner| prop_oneof![
+        prop::collection::vec(inner.clone(), 0..8).prop_map(Value::Array),
+        prop::collection::hash_map(".{0,8}", inner, 0..8)
+            .prop_map(|map| Value::Object(map.into_iter().collect())),
+    ])
+}
+
+fn json_load(load_type_id: &str, actual_value: Value) -> JSONLoad {
+    serde_json::from_value(json!({
+        "load_type_id": load_type_id,
+        "actual_value": actual_value
+    })).expect("JSONLoad should deserialize from any actual_value")
+}
+
+fn load_file(load_type_id: &str, actual_value: Value) -> String {
+    json!({
+        "load_type_id": load_type_id,
+        "actual_value": actual_value
+    }).to_string()
+}
+
+fn transform_fixture() -> Value {
+    json!({
+        "translation": [480.0, 270.0],
+        "scale": [100.0, 200.0],
+        "rotation": 0
+    })
+}
+
+fn texture_fixture() -> Value {
+    json!({
+        "name": "bmo",
+        "image_path": workspace_root().join("assets/images/LZBot.png").to_string_lossy()
+    })
+}
+
+fn path_string(path: &Path) -> String {
+    path.to_string_lossy().into_owned()
+}
+
+fn new_world() -> Arc<RwLock<World>> {
+    let mut ecs = World::new();
+    ecs.register::<Transform>();
+
+    Arc::new(RwLock::new(ecs))
+}
+
+/// Writes an entity file whose only component is `transform` and returns the directory holding both.
+fn transform_entity(transform: &str) -> (TempDir, String) {
+    let dir = tempdir().expect("Failed to create temp dir");
+    let transform_path = dir.path().join("transform.json");
+    let entity_path = dir.path().join("entity.json");
+
+    write(&transform_path, transform).expect("Failed to write transform file");
+    write(&entity_path, load_file(ENTITY_LOAD_ID, json!({ "component_paths": [path_string(&transform_path)] })))
+        .expect("Failed to write entity file");
+
+    (dir, path_string(&entity_path))
+}
+
+/// Runs `create_entity_vec` on `entity_path`, returning None if it panicked.
+fn create_entities(entity_path: String, ecs: Arc<RwLock<World>>) -> Option<Result<()>> {
+    catch_unwind(AssertUnwindSafe(move || create_entity_vec::<FuzzMux>(&vec![entity_path], ecs).map(|_| ()))).ok()
+}
+
+/// Loads a scene stack file pointing at one scene file, returning None if it panicked.
+fn load_scene_stack(scene_stack: &str, scene: &str) -> Option<bool> {
+    let dir = tempdir().expect("Failed to create temp dir");
+    let scene_stack_path = dir.path().join("scene_stack.json");
+    let scene_path = dir.path().join("scene.json");
+
+    write(&scene_stack_path, scene_stack.replace("SCENE_PATH", &path_string(&scene_path).replace('\\', "\\\\")))
+        .expect("Failed to write scene stack file");
+    write(&scene_path, scene).expect("Failed to write scene file");
+
+    let loader = SceneStackLoader::new(path_string(&scene_stack_path), reject_scenes);
+    catch_unwind(AssertUnwindSafe(move || loader.load().run(new_world()).is_ok())).ok()
+}
+
+fn translations(ecs: &Arc<RwLock<World>>) -> Vec<[f32; 2]> {
+    let ecs = ecs.read().expect("Failed to acquire read lock for World");
+    let transforms: ReadStorage<Transform> = ecs.system_data();
+
+    (&transforms).join()
+        .map(|transform| [transform.translation[0].load(Relaxed), transform.translation[1].load(Relaxed)])
+        .collect()
+}
+
+proptest! {
+    #[test]
+    fn create_entity_vec_does_not_panic_on_any_transform(actual_value in arb_json()) {
+        let (_dir, entity_path) = transform_entity(&load_file(TRANSFORM_LOAD_ID, actual_value.clone()));
+        prop_assert!(create_entities(entity_path, new_world()).is_some(), "panicked on {}", actual_value);
+    }
+
+    #[test]
+    fn create_entity_vec_does_not_panic_on_bad_fields(
+        field in prop::sample::select(vec!["translation", "scale", "rotation"]),
+        value in arb_json()
+    ) {
+        let mut actual_value = transform_fixture();
+        actual_value[field] = value;
+
+        let (_dir, entity_path) = transform_entity(&load_file(TRANSFORM_LOAD_ID, actual_value.clone()));
+        prop_assert!(create_entities(entity_path, new_world()).is_some(), "panicked on {}", actual_value);
+    }
+
+    #[test]
+    fn create_entity_vec_does_not_panic_on_malformed_text(contents in ".{0,128}") {
+        let (_dir, entity_path) = transform_entity(&contents);
+        prop_assert!(create_entities(entity_path, new_world()).is_some(), "panicked on {:?}", contents);
+    }
+
+    #[test]
+    fn create_entity_vec_keeps_translation(x in -1.0e6f32..1.0e6, y in -1.0e6f32..1.0e6) {
+        let mut actual_value = transform_fixture();
+        actual_value["translation"] = json!([x, y]);
+
+        let (_dir, entity_path) = transform_entity(&load_file(TRANSFORM_LOAD_ID, actual_value));
+        let ecs = new_world();
+        prop_assert!(matches!(create_entities(entity_path, ecs.clone()), Some(Ok(()))));
+        prop_assert_eq!(translations(&ecs), vec![[x, y]]);
+    }
+
+    // Texture components need a GL context to be created, so only the loader's parsing is reachable here
+    #[test]
+    fn texture_loader_does_not_panic(actual_value in arb_json()) {
+        let json = json_load(TEXTURE_LOAD_ID, actual_value.clone());
+        prop_assert!(catch_unwind(move || TextureLoader::from_json(json).is_ok()).is_ok(), "panicked on {}", actual_value);
+    }
+
+    #[test]
+    fn texture_loader_does_not_panic_on_bad_fields(
+        field in prop::sample::select(vec!["name", "image_path"]),
+        value in arb_json()
+    ) {
+        let mut actual_value = texture_fixture();
+        actual_value[field] = value;
+
+        let json = json_load(TEXTURE_LOAD_ID, actual_value.clone());
+        prop_assert!(catch_unwind(move || TextureLoader::from_json(json).is_ok()).is_ok(), "panicked on {}", actual_value);
+    }
+
+    #[test]
+    fn scene_stack_loader_does_not_panic(
+        load_type_id in prop::sample::select(vec![SCENE_STACK_LOAD_ID, TRANSFORM_LOAD_ID]),
+        actual_value in arb_json()
+    ) {
+        let scene_stack = load_file(load_type_id, actual_value);
+        prop_assert!(load_scene_stack(&scene_stack, "{}").is_some(), "panicked on {}", scene_stack);
+    }
+
+    #[test]
+    fn scene_stack_loader_does_not_panic_on_malformed_text(contents in ".{0,128}") {
+        prop_assert!(load_scene_stack(&contents, "{}").is_some(), "panicked on {:?}", contents);
+    }
+
+    #[test]
+    fn scene_stack_loader_does_not_panic_on_any_scene(scene in arb_json()) {
+        let scene_stack = load_file(SCENE_STACK_LOAD_ID, json!({ "scene_paths": ["SCENE_PATH"] }));
+        prop_assert!(load_scene_stack(&scene_stack, &scene.to_string()).is_some(), "panicked on {}", scene);
+    }
+}
+
+#[test]
+fn fixtures_load() {
+    assert!(TextureLoader::from_json(json_load(TEXTURE_LOAD_ID, texture_fixture())).is_ok());
+
+    // The paths inside the fixture are relative to the workspace root, like the test binaries'
+    enter_workspace_root().expect("Failed to find the workspace assets");
+    let ecs = new_world();
+    assert!(matches!(create_entities(TRANSFORM_ENTITY_FIXTURE.to_string(), ecs.clone()), Some(Ok(()))));
+    assert_eq!(translations(&ecs).len(), 1);
+}
+
+#[test]
+fn scene_stack_loader_reports_factory_errors() {
+    let scene_stack = load_file(SCENE_STACK_LOAD_ID, json!({ "scene_paths": ["SCENE_PATH"] }));
+    let scene = load_file("camera_test_scene", json!({ "entity_paths": [] }));
+
+    assert_eq!(load_scene_stack(&scene_stack, &scene), Some(false));
+}